@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 use tokio_postgres::Client;
 
-use crate::db::queries;
+use crate::db::{queries, value};
 use crate::storage::get_storage_dir;
 
 // ── Settings ──
@@ -150,8 +150,22 @@ async fn execute_readonly_query(
             trimmed.to_string()
         };
 
+        let stmt = client
+            .prepare(&query)
+            .await
+            .map_err(|e| format!("Sorgu hatası: {}", e))?;
+
+        // Columns without a binary decoder are re-selected as text
+        let stmt = match value::text_cast_query(&query, stmt.columns())? {
+            Some(wrapped) => client
+                .prepare(&wrapped)
+                .await
+                .map_err(|_| value::undecodable_error(stmt.columns()))?,
+            None => stmt,
+        };
+
         let rows = client
-            .query(&query as &str, &[])
+            .query(&stmt, &[])
             .await
             .map_err(|e| format!("Sorgu hatası: {}", e))?;
 
@@ -172,7 +186,7 @@ async fn execute_readonly_query(
                 for (idx, col) in row.columns().iter().enumerate() {
                     obj.insert(
                         col.name().to_string(),
-                        queries::pg_value_to_json(row, idx, col.type_()),
+                        queries::pg_value_to_json(row, idx),
                     );
                 }
                 serde_json::Value::Object(obj)
@@ -0,0 +1,566 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tokio::sync::{watch, Mutex};
+use tokio_postgres::Client;
+
+use super::queries::format_db_error;
+
+// ── Server activity (pg_stat_activity) ──────────────────────────────────
+
+pub const DEFAULT_MONITOR_INTERVAL_MS: u64 = 2000;
+const MIN_MONITOR_INTERVAL_MS: u64 = 500;
+/// Failed snapshots in a row after which the monitor gives up
+const MAX_MONITOR_FAILURES: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitySession {
+    pub pid: i32,
+    pub database: Option<String>,
+    pub user_name: Option<String>,
+    pub application_name: String,
+    pub client_addr: Option<String>,
+    pub client_hostname: Option<String>,
+    /// "client backend", "autovacuum worker", "walsender", ...
+    pub backend_type: String,
+    /// "active", "idle", "idle in transaction", ... ; None for background processes
+    pub state: Option<String>,
+    pub wait_event_type: Option<String>,
+    pub wait_event: Option<String>,
+    pub query: String,
+    pub backend_start: Option<String>,
+    pub query_start: Option<String>,
+    /// Time since the current (or last) query started
+    pub query_duration_ms: Option<i64>,
+    /// Time since the open transaction started
+    pub transaction_duration_ms: Option<i64>,
+    /// Pids this session is waiting on
+    pub blocked_by: Vec<i32>,
+    /// One of the app's own connections
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitySnapshot {
+    pub sessions: Vec<ActivitySession>,
+    pub total: usize,
+    pub active: usize,
+    pub idle_in_transaction: usize,
+    pub waiting: usize,
+    pub captured_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityOptions {
+    /// Include idle sessions
+    #[serde(default)]
+    pub include_idle: bool,
+    /// Include autovacuum, walsender and other background processes
+    #[serde(default)]
+    pub include_background: bool,
+    /// Only sessions of the connected database
+    #[serde(default)]
+    pub current_database_only: bool,
+}
+
+pub async fn get_server_activity(
+    client: &Client,
+    options: &ActivityOptions,
+) -> Result<ActivitySnapshot, String> {
+    server_activity(client, options, &[]).await
+}
+
+/// `app_pids` are further backends to flag as the app's own, besides the
+/// one running the query.
+async fn server_activity(
+    client: &Client,
+    options: &ActivityOptions,
+    app_pids: &[i32],
+) -> Result<ActivitySnapshot, String> {
+    let rows = client
+        .query(
+            "SELECT a.pid, a.datname::text, a.usename::text, COALESCE(a.application_name, ''),
+                    host(a.client_addr), a.client_hostname,
+                    COALESCE(a.backend_type, ''), a.state, a.wait_event_type, a.wait_event,
+                    COALESCE(a.query, ''),
+                    a.backend_start::text, a.query_start::text,
+                    (EXTRACT(EPOCH FROM clock_timestamp() - a.query_start) * 1000)::bigint,
+                    (EXTRACT(EPOCH FROM clock_timestamp() - a.xact_start) * 1000)::bigint,
+                    pg_blocking_pids(a.pid),
+                    a.pid = pg_backend_pid() OR a.pid = ANY($4),
+                    clock_timestamp()::text
+             FROM pg_stat_activity a
+             WHERE ($1 OR a.state IS DISTINCT FROM 'idle')
+                AND ($2 OR a.backend_type = 'client backend')
+                AND (NOT $3 OR a.datname = current_database())
+             ORDER BY a.state = 'active' DESC, a.query_start NULLS LAST, a.pid",
+            &[
+                &options.include_idle,
+                &options.include_background,
+                &options.current_database_only,
+                &app_pids,
+            ],
+        )
+        .await
+        .map_err(|e| format!("Sunucu aktivitesi alınamadı: {}", format_db_error(&e)))?;
+
+    let captured_at: String = match rows.first() {
+        Some(row) => row.get(17),
+        None => client
+            .query_one("SELECT clock_timestamp()::text", &[])
+            .await
+            .map_err(|e| format_db_error(&e))?
+            .get(0),
+    };
+
+    let sessions: Vec<ActivitySession> = rows
+        .iter()
+        .map(|row| ActivitySession {
+            pid: row.get(0),
+            database: row.get(1),
+            user_name: row.get(2),
+            application_name: row.get(3),
+            client_addr: row.get(4),
+            client_hostname: row.get(5),
+            backend_type: row.get(6),
+            state: row.get(7),
+            wait_event_type: row.get(8),
+            wait_event: row.get(9),
+            query: row.get(10),
+            backend_start: row.get(11),
+            query_start: row.get(12),
+            query_duration_ms: row.get(13),
+            transaction_duration_ms: row.get(14),
+            blocked_by: row.get(15),
+            is_current: row.get(16),
+        })
+        .collect();
+
+    let count = |state: &str| {
+        sessions
+            .iter()
+            .filter(|s| s.state.as_deref() == Some(state))
+            .count()
+    };
+
+    Ok(ActivitySnapshot {
+        total: sessions.len(),
+        active: count("active"),
+        idle_in_transaction: count("idle in transaction")
+            + count("idle in transaction (aborted)"),
+        waiting: sessions.iter().filter(|s| !s.blocked_by.is_empty()).count(),
+        sessions,
+        captured_at,
+    })
+}
+
+// ── Live monitor ────────────────────────────────────────────────────────
+
+struct RunningMonitor {
+    run_id: String,
+    connection_id: String,
+    stop: watch::Sender<bool>,
+}
+
+pub struct MonitorManager {
+    monitors: HashMap<String, RunningMonitor>,
+}
+
+impl MonitorManager {
+    pub fn new() -> Self {
+        Self {
+            monitors: HashMap::new(),
+        }
+    }
+}
+
+pub type AppMonitorManager = Arc<Mutex<MonitorManager>>;
+
+pub fn create_monitor_manager() -> AppMonitorManager {
+    Arc::new(Mutex::new(MonitorManager::new()))
+}
+
+/// Sends a snapshot every `interval_ms` until `stop_activity_monitor` is
+/// called for `monitor_id`. Polling runs on its own `session` so it neither
+/// queues behind nor delays the app's queries on `client`; a failed snapshot
+/// is skipped, and only `MAX_MONITOR_FAILURES` in a row (or a lost session)
+/// end the monitor with the last error.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_activity_monitor(
+    client: &Arc<Client>,
+    session: Client,
+    monitors: &AppMonitorManager,
+    connection_id: &str,
+    monitor_id: &str,
+    options: &ActivityOptions,
+    interval_ms: Option<u64>,
+    channel: Channel<ActivitySnapshot>,
+) -> Result<(), String> {
+    let interval = interval_ms
+        .unwrap_or(DEFAULT_MONITOR_INTERVAL_MS)
+        .max(MIN_MONITOR_INTERVAL_MS);
+    let app_pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+
+    let (stop, mut stopped) = watch::channel(false);
+    let run_id = uuid::Uuid::new_v4().to_string();
+    {
+        let mut manager = monitors.lock().await;
+        if let Some(previous) = manager.monitors.insert(
+            monitor_id.to_string(),
+            RunningMonitor {
+                run_id: run_id.clone(),
+                connection_id: connection_id.to_string(),
+                stop,
+            },
+        ) {
+            previous.stop.send(true).ok();
+        }
+    }
+
+    let mut ticker = tokio::time::interval(Duration::from_millis(interval));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut failures = 0;
+    let result = loop {
+        tokio::select! {
+            _ = stopped.changed() => break Ok(()),
+            _ = ticker.tick() => {
+                match server_activity(&session, options, &[app_pid]).await {
+                    Ok(snapshot) => {
+                        failures = 0;
+                        if channel.send(snapshot).is_err() {
+                            break Ok(());
+                        }
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        if failures >= MAX_MONITOR_FAILURES || session.is_closed() {
+                            break Err(e);
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    // A restarted monitor with the same id has already replaced this entry
+    let mut manager = monitors.lock().await;
+    if manager
+        .monitors
+        .get(monitor_id)
+        .is_some_and(|m| m.run_id == run_id)
+    {
+        manager.monitors.remove(monitor_id);
+    }
+    result
+}
+
+pub async fn stop_activity_monitor(monitors: &AppMonitorManager, monitor_id: &str) {
+    let mut manager = monitors.lock().await;
+    if let Some(monitor) = manager.monitors.remove(monitor_id) {
+        monitor.stop.send(true).ok();
+    }
+}
+
+pub async fn stop_connection_monitors(monitors: &AppMonitorManager, connection_id: &str) {
+    let mut manager = monitors.lock().await;
+    manager.monitors.retain(|_, monitor| {
+        if monitor.connection_id == connection_id {
+            monitor.stop.send(true).ok();
+            false
+        } else {
+            true
+        }
+    });
+}
+
+// ── Lock tree ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockDetail {
+    pub lock_type: String,
+    pub mode: String,
+    pub granted: bool,
+    pub relation: Option<String>,
+}
+
+/// A session in a blocking chain; `children` are the sessions it blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockNode {
+    pub pid: i32,
+    pub user_name: Option<String>,
+    pub application_name: String,
+    pub state: Option<String>,
+    pub query: String,
+    /// Relation of the lock being waited for, or for a blocker the relation
+    /// its waiters are queued on
+    pub relation: Option<String>,
+    pub lock_mode: Option<String>,
+    /// How long the session has been waiting for its lock (PG14+, the
+    /// current query's runtime before that); None for non-waiting blockers
+    pub waiting_ms: Option<i64>,
+    pub transaction_duration_ms: Option<i64>,
+    pub blocked_by: Vec<i32>,
+    pub locks: Vec<LockDetail>,
+    pub children: Vec<LockNode>,
+}
+
+struct LockSession {
+    user_name: Option<String>,
+    application_name: String,
+    state: Option<String>,
+    query: String,
+    query_duration_ms: Option<i64>,
+    transaction_duration_ms: Option<i64>,
+}
+
+/// Sessions that block others, as trees rooted at the sessions that are
+/// not waiting themselves.
+pub async fn get_lock_tree(client: &Arc<Client>) -> Result<Vec<LockNode>, String> {
+    let rows = client
+        .query(
+            "SELECT pid, pg_blocking_pids(pid)
+             FROM pg_stat_activity
+             WHERE cardinality(pg_blocking_pids(pid)) > 0",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Kilit bilgisi alınamadı: {}", format_db_error(&e)))?;
+
+    let mut blocked_by: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut involved: Vec<i32> = Vec::new();
+    for row in &rows {
+        let pid: i32 = row.get(0);
+        let blockers: Vec<i32> = row.get(1);
+        for p in std::iter::once(pid).chain(blockers.iter().copied()) {
+            if !involved.contains(&p) {
+                involved.push(p);
+            }
+        }
+        blocked_by.insert(pid, blockers);
+    }
+    if involved.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let session_rows = client
+        .query(
+            "SELECT pid, usename::text, COALESCE(application_name, ''), state, COALESCE(query, ''),
+                    (EXTRACT(EPOCH FROM clock_timestamp() - query_start) * 1000)::bigint,
+                    (EXTRACT(EPOCH FROM clock_timestamp() - xact_start) * 1000)::bigint
+             FROM pg_stat_activity
+             WHERE pid = ANY($1)",
+            &[&involved],
+        )
+        .await
+        .map_err(|e| format!("Kilit bilgisi alınamadı: {}", format_db_error(&e)))?;
+    let sessions: HashMap<i32, LockSession> = session_rows
+        .iter()
+        .map(|row| {
+            (
+                row.get(0),
+                LockSession {
+                    user_name: row.get(1),
+                    application_name: row.get(2),
+                    state: row.get(3),
+                    query: row.get(4),
+                    query_duration_ms: row.get(5),
+                    transaction_duration_ms: row.get(6),
+                },
+            )
+        })
+        .collect();
+
+    // pg_locks.waitstart exists from PG14 on
+    let version: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .await
+        .map_err(|e| format!("Kilit bilgisi alınamadı: {}", format_db_error(&e)))?
+        .get(0);
+    let lock_sql = format!(
+        "SELECT l.pid, l.locktype, l.mode, l.granted,
+                CASE WHEN l.relation IS NOT NULL THEN l.relation::regclass::text END,
+                {}
+         FROM pg_locks l
+         WHERE l.pid = ANY($1) AND (NOT l.granted OR l.relation IS NOT NULL)
+         ORDER BY l.pid, l.granted, l.relation",
+        if version >= 140000 {
+            "(EXTRACT(EPOCH FROM clock_timestamp() - l.waitstart) * 1000)::bigint"
+        } else {
+            "NULL::bigint"
+        }
+    );
+    let lock_rows = client
+        .query(&lock_sql, &[&involved])
+        .await
+        .map_err(|e| format!("Kilit bilgisi alınamadı: {}", format_db_error(&e)))?;
+    let mut locks: HashMap<i32, Vec<LockDetail>> = HashMap::new();
+    let mut waits: HashMap<i32, i64> = HashMap::new();
+    for row in &lock_rows {
+        if let Some(waited) = row.get::<_, Option<i64>>(5) {
+            waits.entry(row.get(0)).or_insert(waited);
+        }
+        locks.entry(row.get(0)).or_default().push(LockDetail {
+            lock_type: row.get(1),
+            mode: row.get(2),
+            granted: row.get(3),
+            relation: row.get(4),
+        });
+    }
+
+    let mut blocks: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (pid, blockers) in &blocked_by {
+        for blocker in blockers {
+            blocks.entry(*blocker).or_default().push(*pid);
+        }
+    }
+    for children in blocks.values_mut() {
+        children.sort_unstable();
+    }
+
+    let ctx = LockTreeContext {
+        sessions: &sessions,
+        locks: &locks,
+        waits: &waits,
+        blocked_by: &blocked_by,
+        blocks: &blocks,
+    };
+    let mut roots: Vec<i32> = involved
+        .iter()
+        .copied()
+        .filter(|pid| !blocked_by.contains_key(pid))
+        .collect();
+    roots.sort_unstable();
+
+    Ok(roots
+        .into_iter()
+        .map(|pid| ctx.node(pid, &mut Vec::new()))
+        .collect())
+}
+
+struct LockTreeContext<'a> {
+    sessions: &'a HashMap<i32, LockSession>,
+    locks: &'a HashMap<i32, Vec<LockDetail>>,
+    /// Milliseconds since each waiting pid's lock wait began (PG14+)
+    waits: &'a HashMap<i32, i64>,
+    blocked_by: &'a HashMap<i32, Vec<i32>>,
+    blocks: &'a HashMap<i32, Vec<i32>>,
+}
+
+impl LockTreeContext<'_> {
+    fn waiting_lock(&self, pid: i32) -> Option<&LockDetail> {
+        self.locks.get(&pid)?.iter().find(|l| !l.granted)
+    }
+
+    /// `path` holds the pids above this node so a cycle can't recurse forever.
+    fn node(&self, pid: i32, path: &mut Vec<i32>) -> LockNode {
+        let session = self.sessions.get(&pid);
+        let blocked_by = self.blocked_by.get(&pid).cloned().unwrap_or_default();
+        let locks = self.locks.get(&pid).cloned().unwrap_or_default();
+        let child_pids = self.blocks.get(&pid).cloned().unwrap_or_default();
+
+        let (relation, lock_mode) = match self.waiting_lock(pid) {
+            Some(waiting) => (waiting.relation.clone(), Some(waiting.mode.clone())),
+            None => {
+                // The granted lock on a relation one of the waiters is queued on
+                let held = child_pids.iter().find_map(|child| {
+                    let wanted = self.waiting_lock(*child)?.relation.as_ref()?;
+                    locks
+                        .iter()
+                        .find(|l| l.granted && l.relation.as_ref() == Some(wanted))
+                });
+                match held {
+                    Some(lock) => (lock.relation.clone(), Some(lock.mode.clone())),
+                    None => (None, None),
+                }
+            }
+        };
+
+        path.push(pid);
+        let mut children = Vec::new();
+        for child in &child_pids {
+            if !path.contains(child) {
+                children.push(self.node(*child, path));
+            }
+        }
+        path.pop();
+
+        LockNode {
+            pid,
+            user_name: session.and_then(|s| s.user_name.clone()),
+            application_name: session
+                .map(|s| s.application_name.clone())
+                .unwrap_or_default(),
+            state: session.and_then(|s| s.state.clone()),
+            query: session.map(|s| s.query.clone()).unwrap_or_default(),
+            relation,
+            lock_mode,
+            waiting_ms: if blocked_by.is_empty() {
+                None
+            } else {
+                // waitstart is briefly NULL right after a wait begins
+                self.waits
+                    .get(&pid)
+                    .copied()
+                    .or_else(|| session.and_then(|s| s.query_duration_ms))
+            },
+            transaction_duration_ms: session.and_then(|s| s.transaction_duration_ms),
+            blocked_by,
+            locks,
+            children,
+        }
+    }
+}
+
+// ── Cancel / terminate ──────────────────────────────────────────────────
+
+async fn signal_backend(client: &Arc<Client>, pid: i32, function: &str) -> Result<bool, String> {
+    let own_pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    if pid == own_pid {
+        return Err("Uygulamanın kendi bağlantısı sonlandırılamaz".to_string());
+    }
+
+    let row = client
+        .query_one(&format!("SELECT {}($1)", function), &[&pid])
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(row.get(0))
+}
+
+/// Cancels the running query of `pid`; false if no such backend exists.
+pub async fn cancel_backend(client: &Arc<Client>, pid: i32) -> Result<bool, String> {
+    signal_backend(client, pid, "pg_cancel_backend").await
+}
+
+/// Closes the session of `pid`; false if no such backend exists.
+pub async fn terminate_backend(client: &Arc<Client>, pid: i32) -> Result<bool, String> {
+    signal_backend(client, pid, "pg_terminate_backend").await
+}
+
+/// Terminates `pid` only if it still blocks other sessions without waiting
+/// on anyone itself, so a stale tree can't kill an unrelated session.
+pub async fn terminate_root_blocker(client: &Arc<Client>, pid: i32) -> Result<bool, String> {
+    let is_root: bool = client
+        .query_one(
+            "SELECT EXISTS (SELECT 1 FROM pg_stat_activity WHERE $1 = ANY(pg_blocking_pids(pid)))
+                AND cardinality(pg_blocking_pids($1)) = 0",
+            &[&pid],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    if !is_root {
+        return Err(format!("{} numaralı oturum artık kök engelleyici değil", pid));
+    }
+    terminate_backend(client, pid).await
+}
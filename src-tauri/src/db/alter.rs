@@ -0,0 +1,430 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident};
+
+// ── Structured ALTER TABLE ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+impl ReferentialAction {
+    fn sql(self) -> &'static str {
+        match self {
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::SetDefault => "SET DEFAULT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConstraintDefinition {
+    PrimaryKey {
+        columns: Vec<String>,
+    },
+    Unique {
+        columns: Vec<String>,
+    },
+    Check {
+        expression: String,
+    },
+    ForeignKey {
+        columns: Vec<String>,
+        ref_schema: String,
+        ref_table: String,
+        ref_columns: Vec<String>,
+        #[serde(default)]
+        on_delete: Option<ReferentialAction>,
+        #[serde(default)]
+        on_update: Option<ReferentialAction>,
+    },
+}
+
+/// One change of a change set. Types, defaults, `USING` and `CHECK`
+/// expressions are SQL fragments; names are quoted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TableChange {
+    AddColumn {
+        name: String,
+        data_type: String,
+        #[serde(default = "default_true")]
+        nullable: bool,
+        #[serde(default)]
+        default_value: Option<String>,
+    },
+    DropColumn {
+        name: String,
+        #[serde(default)]
+        cascade: bool,
+    },
+    RenameColumn {
+        name: String,
+        new_name: String,
+    },
+    ChangeType {
+        name: String,
+        data_type: String,
+        #[serde(default)]
+        using: Option<String>,
+    },
+    SetDefault {
+        name: String,
+        default_value: String,
+    },
+    DropDefault {
+        name: String,
+    },
+    SetNotNull {
+        name: String,
+    },
+    DropNotNull {
+        name: String,
+    },
+    AddConstraint {
+        #[serde(default)]
+        name: Option<String>,
+        constraint: ConstraintDefinition,
+    },
+    DropConstraint {
+        name: String,
+        #[serde(default)]
+        cascade: bool,
+    },
+    RenameTable {
+        new_name: String,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn ident(name: &str, what: &str) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} adı boş olamaz", what));
+    }
+    Ok(quote_ident(name))
+}
+
+fn fragment<'a>(sql: &'a str, what: &str) -> Result<&'a str, String> {
+    let trimmed = sql.trim();
+    if trimmed.is_empty() {
+        return Err(format!("{} boş olamaz", what));
+    }
+    Ok(trimmed)
+}
+
+fn column_list(columns: &[String]) -> Result<String, String> {
+    if columns.is_empty() {
+        return Err("Kısıtlama için en az bir kolon gerekli".to_string());
+    }
+    let quoted = columns
+        .iter()
+        .map(|c| ident(c, "Kolon"))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(quoted.join(", "))
+}
+
+fn constraint_sql(constraint: &ConstraintDefinition) -> Result<String, String> {
+    Ok(match constraint {
+        ConstraintDefinition::PrimaryKey { columns } => {
+            format!("PRIMARY KEY ({})", column_list(columns)?)
+        }
+        ConstraintDefinition::Unique { columns } => {
+            format!("UNIQUE ({})", column_list(columns)?)
+        }
+        ConstraintDefinition::Check { expression } => {
+            format!("CHECK ({})", fragment(expression, "CHECK ifadesi")?)
+        }
+        ConstraintDefinition::ForeignKey {
+            columns,
+            ref_schema,
+            ref_table,
+            ref_columns,
+            on_delete,
+            on_update,
+        } => {
+            if columns.len() != ref_columns.len() {
+                return Err("Yabancı anahtar kolon sayıları eşleşmiyor".to_string());
+            }
+            let mut sql = format!(
+                "FOREIGN KEY ({}) REFERENCES {}.{} ({})",
+                column_list(columns)?,
+                ident(ref_schema, "Şema")?,
+                ident(ref_table, "Tablo")?,
+                column_list(ref_columns)?
+            );
+            if let Some(action) = on_delete {
+                sql.push_str(&format!(" ON DELETE {}", action.sql()));
+            }
+            if let Some(action) = on_update {
+                sql.push_str(&format!(" ON UPDATE {}", action.sql()));
+            }
+            sql
+        }
+    })
+}
+
+/// Renders the statements for a change set, in the order they will run.
+/// A table rename always runs last so earlier statements can use the old name.
+pub fn build_alter_statements(
+    schema: &str,
+    table: &str,
+    changes: &[TableChange],
+) -> Result<Vec<String>, String> {
+    let qualified = format!("{}.{}", ident(schema, "Şema")?, ident(table, "Tablo")?);
+
+    let mut statements = Vec::new();
+    let mut rename: Option<String> = None;
+
+    for change in changes {
+        let action = match change {
+            TableChange::AddColumn {
+                name,
+                data_type,
+                nullable,
+                default_value,
+            } => {
+                let mut sql = format!(
+                    "ADD COLUMN {} {}",
+                    ident(name, "Kolon")?,
+                    fragment(data_type, "Veri tipi")?
+                );
+                if let Some(default) = default_value {
+                    sql.push_str(&format!(" DEFAULT {}", fragment(default, "Varsayılan değer")?));
+                }
+                if !nullable {
+                    sql.push_str(" NOT NULL");
+                }
+                sql
+            }
+            TableChange::DropColumn { name, cascade } => format!(
+                "DROP COLUMN {}{}",
+                ident(name, "Kolon")?,
+                if *cascade { " CASCADE" } else { "" }
+            ),
+            TableChange::RenameColumn { name, new_name } => format!(
+                "RENAME COLUMN {} TO {}",
+                ident(name, "Kolon")?,
+                ident(new_name, "Yeni kolon")?
+            ),
+            TableChange::ChangeType {
+                name,
+                data_type,
+                using,
+            } => {
+                let mut sql = format!(
+                    "ALTER COLUMN {} TYPE {}",
+                    ident(name, "Kolon")?,
+                    fragment(data_type, "Veri tipi")?
+                );
+                if let Some(using) = using {
+                    sql.push_str(&format!(" USING {}", fragment(using, "USING ifadesi")?));
+                }
+                sql
+            }
+            TableChange::SetDefault {
+                name,
+                default_value,
+            } => format!(
+                "ALTER COLUMN {} SET DEFAULT {}",
+                ident(name, "Kolon")?,
+                fragment(default_value, "Varsayılan değer")?
+            ),
+            TableChange::DropDefault { name } => {
+                format!("ALTER COLUMN {} DROP DEFAULT", ident(name, "Kolon")?)
+            }
+            TableChange::SetNotNull { name } => {
+                format!("ALTER COLUMN {} SET NOT NULL", ident(name, "Kolon")?)
+            }
+            TableChange::DropNotNull { name } => {
+                format!("ALTER COLUMN {} DROP NOT NULL", ident(name, "Kolon")?)
+            }
+            TableChange::AddConstraint { name, constraint } => match name {
+                Some(name) if !name.trim().is_empty() => format!(
+                    "ADD CONSTRAINT {} {}",
+                    quote_ident(name),
+                    constraint_sql(constraint)?
+                ),
+                _ => format!("ADD {}", constraint_sql(constraint)?),
+            },
+            TableChange::DropConstraint { name, cascade } => format!(
+                "DROP CONSTRAINT {}{}",
+                ident(name, "Kısıtlama")?,
+                if *cascade { " CASCADE" } else { "" }
+            ),
+            TableChange::RenameTable { new_name } => {
+                if rename.is_some() {
+                    return Err("Tablo yalnızca bir kez yeniden adlandırılabilir".to_string());
+                }
+                rename = Some(format!("RENAME TO {}", ident(new_name, "Yeni tablo")?));
+                continue;
+            }
+        };
+        statements.push(format!("ALTER TABLE {} {};", qualified, action));
+    }
+
+    if let Some(action) = rename {
+        statements.push(format!("ALTER TABLE {} {};", qualified, action));
+    }
+
+    if statements.is_empty() {
+        return Err("Uygulanacak değişiklik yok".to_string());
+    }
+    Ok(statements)
+}
+
+/// Runs the whole change set in one transaction and returns the statements.
+/// Nothing is applied if any statement fails. Each statement is sent on its
+/// own through the extended protocol, so a fragment smuggling in a second
+/// statement is rejected instead of running outside the preview.
+pub async fn apply_table_changes(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    changes: &[TableChange],
+) -> Result<Vec<String>, String> {
+    let statements = build_alter_statements(schema, table, changes)?;
+
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
+
+    for statement in &statements {
+        if let Err(e) = client.execute(statement.as_str(), &[]).await {
+            client.execute("ROLLBACK", &[]).await.ok();
+            return Err(format!("{}\n\n{}", format_db_error(&e), statement));
+        }
+    }
+
+    if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
+    }
+
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(changes: Vec<TableChange>) -> Result<Vec<String>, String> {
+        build_alter_statements("public", "users", &changes)
+    }
+
+    #[test]
+    fn columns_are_quoted_and_options_appended() {
+        let statements = build(vec![
+            TableChange::AddColumn {
+                name: "Created At".to_string(),
+                data_type: "timestamptz".to_string(),
+                nullable: false,
+                default_value: Some(" now() ".to_string()),
+            },
+            TableChange::ChangeType {
+                name: "age".to_string(),
+                data_type: "int8".to_string(),
+                using: Some("age::int8".to_string()),
+            },
+            TableChange::DropColumn { name: "old\"col".to_string(), cascade: true },
+        ])
+        .unwrap();
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE \"public\".\"users\" ADD COLUMN \"Created At\" timestamptz DEFAULT now() NOT NULL;",
+                "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"age\" TYPE int8 USING age::int8;",
+                "ALTER TABLE \"public\".\"users\" DROP COLUMN \"old\"\"col\" CASCADE;",
+            ]
+        );
+    }
+
+    #[test]
+    fn table_rename_runs_last() {
+        let statements = build(vec![
+            TableChange::RenameTable { new_name: "people".to_string() },
+            TableChange::SetNotNull { name: "email".to_string() },
+        ])
+        .unwrap();
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"email\" SET NOT NULL;",
+                "ALTER TABLE \"public\".\"users\" RENAME TO \"people\";",
+            ]
+        );
+    }
+
+    #[test]
+    fn foreign_keys_render_actions() {
+        let statements = build(vec![TableChange::AddConstraint {
+            name: Some("users_org_fk".to_string()),
+            constraint: ConstraintDefinition::ForeignKey {
+                columns: vec!["org_id".to_string()],
+                ref_schema: "public".to_string(),
+                ref_table: "orgs".to_string(),
+                ref_columns: vec!["id".to_string()],
+                on_delete: Some(ReferentialAction::Cascade),
+                on_update: Some(ReferentialAction::NoAction),
+            },
+        }])
+        .unwrap();
+        assert_eq!(
+            statements[0],
+            "ALTER TABLE \"public\".\"users\" ADD CONSTRAINT \"users_org_fk\" FOREIGN KEY (\"org_id\") \
+             REFERENCES \"public\".\"orgs\" (\"id\") ON DELETE CASCADE ON UPDATE NO ACTION;"
+        );
+    }
+
+    #[test]
+    fn unnamed_constraints_omit_the_name() {
+        let statements = build(vec![TableChange::AddConstraint {
+            name: Some("  ".to_string()),
+            constraint: ConstraintDefinition::Unique {
+                columns: vec!["a".to_string(), "b".to_string()],
+            },
+        }])
+        .unwrap();
+        assert_eq!(statements[0], "ALTER TABLE \"public\".\"users\" ADD UNIQUE (\"a\", \"b\");");
+    }
+
+    #[test]
+    fn invalid_change_sets_are_rejected() {
+        assert!(build(Vec::new()).is_err());
+        assert!(build(vec![TableChange::DropDefault { name: " ".to_string() }]).is_err());
+        assert!(build(vec![
+            TableChange::RenameTable { new_name: "a".to_string() },
+            TableChange::RenameTable { new_name: "b".to_string() },
+        ])
+        .is_err());
+        assert!(build(vec![TableChange::AddConstraint {
+            name: None,
+            constraint: ConstraintDefinition::ForeignKey {
+                columns: vec!["a".to_string(), "b".to_string()],
+                ref_schema: "public".to_string(),
+                ref_table: "t".to_string(),
+                ref_columns: vec!["id".to_string()],
+                on_delete: None,
+                on_update: None,
+            },
+        }])
+        .is_err());
+        assert!(build(vec![TableChange::AddConstraint {
+            name: None,
+            constraint: ConstraintDefinition::Check { expression: "".to_string() },
+        }])
+        .is_err());
+    }
+}
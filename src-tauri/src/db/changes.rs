@@ -0,0 +1,733 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::{Client, Row, Statement};
+
+use super::queries::{format_db_error, pg_value_to_json, quote_ident, TableColumnMeta};
+use super::value;
+
+// ── Grid edits ──────────────────────────────────────────────────────────
+
+pub(crate) const CONFLICT_ERROR: &str =
+    "Satır yüklendikten sonra başka bir oturum tarafından değiştirildi";
+
+const RETURNING_SAVEPOINT: &str = "dbleaf_returning";
+
+pub(crate) const ROW_MOVED_ERROR: &str =
+    "Satır bulunamadı; okunduktan sonra değiştirilmiş veya taşınmış olabilir. Verileri yenileyin";
+
+/// Identifies one row: primary key columns, or the fallback from `get_row_identity`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowKey {
+    pub columns: Vec<String>,
+    pub values: Vec<Option<String>>,
+    pub types: Vec<String>,
+}
+
+impl RowKey {
+    /// A `ctid` key stops matching once the row is updated, since every
+    /// update writes a new row version.
+    pub(crate) fn uses_ctid(&self) -> bool {
+        self.columns.iter().any(|c| c == "ctid")
+    }
+}
+
+/// What the grid saw when it loaded the row. An update only goes through if
+/// the row still matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RowExpectation {
+    /// Previous values of the updated columns, in the same order
+    #[serde(default)]
+    pub original_values: Option<Vec<Option<String>>>,
+    #[serde(default)]
+    pub xmin: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    Modified,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowConflict {
+    pub kind: ConflictKind,
+    pub columns: Vec<TableColumnMeta>,
+    /// The row as it is in the database now; None when it was deleted
+    pub current_values: Option<Vec<serde_json::Value>>,
+    pub current_xmin: Option<String>,
+}
+
+/// Rows as stored after an insert or update, including defaults, generated
+/// columns and trigger changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReturnedRows {
+    pub affected_rows: u64,
+    pub columns: Vec<TableColumnMeta>,
+    /// Empty when the stored rows may not be read back (no SELECT privilege
+    /// or a row-level security policy hides them)
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// New `xmin` of each row for the next update; None for views
+    pub row_xmins: Vec<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRowResult {
+    #[serde(flatten)]
+    pub returned: ReturnedRows,
+    pub conflict: Option<RowConflict>,
+}
+
+/// Values are sent as text and cast to the given column types.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RowChange {
+    Insert {
+        columns: Vec<String>,
+        values: Vec<Option<String>>,
+        column_types: Vec<String>,
+    },
+    Update {
+        key: RowKey,
+        columns: Vec<String>,
+        values: Vec<Option<String>>,
+        column_types: Vec<String>,
+        #[serde(default)]
+        expected: Option<RowExpectation>,
+    },
+    Delete {
+        key: RowKey,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChange {
+    /// Echoed back in the result so the grid can find the row
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub change: RowChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeStatus {
+    /// Ran; only kept if the batch was committed
+    Applied,
+    Failed,
+    /// Not run because an earlier change failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeResult {
+    pub index: usize,
+    pub id: Option<String>,
+    pub status: ChangeStatus,
+    pub affected_rows: u64,
+    pub error: Option<String>,
+    pub conflict: Option<RowConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyChangesResult {
+    pub committed: bool,
+    pub results: Vec<ChangeResult>,
+    /// Index of the change that caused the rollback
+    pub failed_index: Option<usize>,
+    pub execution_time_ms: u128,
+}
+
+fn placeholders(
+    columns: &[String],
+    types: &[String],
+    first_param: usize,
+    separator: &str,
+) -> Result<String, String> {
+    if columns.len() != types.len() {
+        return Err("Kolon ve tip sayısı eşleşmiyor".to_string());
+    }
+    Ok(columns
+        .iter()
+        .zip(types)
+        .enumerate()
+        .map(|(i, (col, typ))| format!("{} = ${}::{}", quote_ident(col), first_param + i, typ))
+        .collect::<Vec<_>>()
+        .join(separator))
+}
+
+fn key_clause(key: &RowKey, first_param: usize) -> Result<String, String> {
+    if key.columns.is_empty() {
+        return Err("Birincil anahtar bulunamadı".to_string());
+    }
+    if key.values.len() != key.columns.len() {
+        return Err("PK değer sayısı kolon sayısıyla eşleşmiyor".to_string());
+    }
+    placeholders(&key.columns, &key.types, first_param, " AND ")
+}
+
+/// Conditions that the row still holds the expected values. json and xml
+/// have no equality operator and are compared as jsonb and text.
+fn expectation_clause(
+    expected: &RowExpectation,
+    columns: &[String],
+    column_types: &[String],
+    first_param: usize,
+) -> Result<(Vec<String>, Vec<Option<String>>), String> {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+    if let Some(original) = &expected.original_values {
+        if original.len() != columns.len() {
+            return Err("Önceki değer sayısı kolon sayısıyla eşleşmiyor".to_string());
+        }
+        for ((col, typ), value) in columns.iter().zip(column_types).zip(original) {
+            let n = first_param + values.len();
+            let col = quote_ident(col);
+            conditions.push(match typ.to_lowercase().as_str() {
+                "json" => format!("{}::jsonb IS NOT DISTINCT FROM ${}::jsonb", col, n),
+                "xml" => format!("{}::text IS NOT DISTINCT FROM ${}", col, n),
+                _ => format!("{} IS NOT DISTINCT FROM ${}::{}", col, n, typ),
+            });
+            values.push(value.clone());
+        }
+    }
+    if let Some(xmin) = &expected.xmin {
+        conditions.push(format!("xmin = ${}::xid", first_param + values.len()));
+        values.push(Some(xmin.clone()));
+    }
+    Ok((conditions, values))
+}
+
+/// SQL and text parameters for one change.
+pub(crate) fn build_change_sql(
+    schema: &str,
+    table: &str,
+    change: &RowChange,
+) -> Result<(String, Vec<Option<String>>), String> {
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    match change {
+        RowChange::Insert {
+            columns,
+            values,
+            column_types,
+        } => {
+            if columns.is_empty() {
+                return Err("Kolon belirtilmedi".to_string());
+            }
+            if columns.len() != values.len() || columns.len() != column_types.len() {
+                return Err("Kolon ve değer sayısı eşleşmiyor".to_string());
+            }
+            let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+            let val_list: Vec<String> = column_types
+                .iter()
+                .enumerate()
+                .map(|(i, typ)| format!("${}::{}", i + 1, typ))
+                .collect();
+            Ok((
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    qualified,
+                    col_list.join(", "),
+                    val_list.join(", ")
+                ),
+                values.clone(),
+            ))
+        }
+        RowChange::Update {
+            key,
+            columns,
+            values,
+            column_types,
+            expected,
+        } => {
+            if columns.is_empty() {
+                return Err("Güncellenecek kolon bulunamadı".to_string());
+            }
+            if columns.len() != values.len() {
+                return Err("Kolon ve değer sayısı eşleşmiyor".to_string());
+            }
+            let set_clause = placeholders(columns, column_types, 1, ", ")?;
+            let mut where_clause = key_clause(key, columns.len() + 1)?;
+            let mut params: Vec<Option<String>> = values.iter().chain(&key.values).cloned().collect();
+            if let Some(expected) = expected {
+                let (conditions, expected_values) =
+                    expectation_clause(expected, columns, column_types, params.len() + 1)?;
+                for condition in conditions {
+                    where_clause.push_str(&format!(" AND {}", condition));
+                }
+                params.extend(expected_values);
+            }
+            Ok((
+                format!("UPDATE {} SET {} WHERE {}", qualified, set_clause, where_clause),
+                params,
+            ))
+        }
+        RowChange::Delete { key } => Ok((
+            format!("DELETE FROM {} WHERE {}", qualified, key_clause(key, 1)?),
+            key.values.clone(),
+        )),
+    }
+}
+
+async fn prepare_change(
+    client: &Arc<Client>,
+    sql: &str,
+    values: &[Option<String>],
+) -> Result<Statement, String> {
+    let param_types: Vec<Type> = vec![Type::TEXT; values.len()];
+    client
+        .prepare_typed(sql, &param_types)
+        .await
+        .map_err(|e| format_db_error(&e))
+}
+
+fn as_params(values: &[Option<String>]) -> Vec<&(dyn ToSql + Sync)> {
+    values.iter().map(|v| v as &(dyn ToSql + Sync)).collect()
+}
+
+pub(crate) async fn execute_change(
+    client: &Arc<Client>,
+    sql: &str,
+    values: &[Option<String>],
+) -> Result<u64, String> {
+    let stmt = prepare_change(client, sql, values).await?;
+    client
+        .execute(&stmt, &as_params(values))
+        .await
+        .map_err(|e| format_db_error(&e))
+}
+
+/// Column list of a table or view, with a select list that decodes into
+/// JSON. Views have no `xmin`.
+pub(crate) struct TableShape {
+    columns: Vec<TableColumnMeta>,
+    select_list: String,
+    has_xmin: bool,
+}
+
+impl TableShape {
+    fn output_list(&self) -> String {
+        if self.has_xmin {
+            format!("{}, xmin::text AS dbleaf_xmin", self.select_list)
+        } else {
+            self.select_list.clone()
+        }
+    }
+
+    fn values(&self, row: &Row) -> Vec<serde_json::Value> {
+        (0..self.columns.len()).map(|i| pg_value_to_json(row, i)).collect()
+    }
+
+    fn xmin(&self, row: &Row) -> Option<String> {
+        if self.has_xmin {
+            row.get(self.columns.len())
+        } else {
+            None
+        }
+    }
+}
+
+pub(crate) async fn table_shape(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+) -> Result<TableShape, String> {
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    let stmt = client
+        .prepare(&format!("SELECT * FROM {} LIMIT 0", qualified))
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    let has_xmin: bool = client
+        .query_one(
+            "SELECT EXISTS (
+                SELECT 1 FROM pg_attribute
+                WHERE attrelid = $1::text::regclass AND attname = 'xmin' AND attnum < 0
+             )",
+            &[&qualified],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+
+    Ok(TableShape {
+        columns: stmt
+            .columns()
+            .iter()
+            .map(|col| TableColumnMeta {
+                name: col.name().to_string(),
+                data_type: col.type_().name().to_string(),
+            })
+            .collect(),
+        select_list: value::select_list(stmt.columns(), None),
+        has_xmin,
+    })
+}
+
+/// Runs an insert or update with `RETURNING` and decodes the stored rows.
+/// If reading them back is not permitted, the change is run again without
+/// `RETURNING` and only the row count is reported; a savepoint keeps an open
+/// transaction usable for that second attempt.
+pub(crate) async fn execute_change_returning(
+    client: &Arc<Client>,
+    shape: &TableShape,
+    schema: &str,
+    table: &str,
+    change: &RowChange,
+) -> Result<ReturnedRows, String> {
+    let (sql, values) = build_change_sql(schema, table, change)?;
+    let returning_sql = format!("{} RETURNING {}", sql, shape.output_list());
+
+    // Fails outside a transaction block, where a failed statement leaves
+    // nothing to clean up
+    let savepoint = client
+        .batch_execute(&format!("SAVEPOINT {}", RETURNING_SAVEPOINT))
+        .await
+        .is_ok();
+    let param_types: Vec<Type> = vec![Type::TEXT; values.len()];
+    let result = match client.prepare_typed(&returning_sql, &param_types).await {
+        Ok(stmt) => client.query(&stmt, &as_params(&values)).await,
+        Err(e) => Err(e),
+    };
+    let cleanup = match (&result, savepoint) {
+        (_, false) => None,
+        (Ok(_), true) => Some(format!("RELEASE SAVEPOINT {}", RETURNING_SAVEPOINT)),
+        (Err(_), true) => Some(format!(
+            "ROLLBACK TO SAVEPOINT {0}; RELEASE SAVEPOINT {0}",
+            RETURNING_SAVEPOINT
+        )),
+    };
+    if let Some(cleanup) = cleanup {
+        client.batch_execute(&cleanup).await.ok();
+    }
+
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(e) if e.code() == Some(&SqlState::INSUFFICIENT_PRIVILEGE) => {
+            return Ok(ReturnedRows {
+                affected_rows: execute_change(client, &sql, &values).await?,
+                columns: shape.columns.clone(),
+                rows: Vec::new(),
+                row_xmins: Vec::new(),
+            });
+        }
+        Err(e) => return Err(format_db_error(&e)),
+    };
+
+    Ok(ReturnedRows {
+        affected_rows: rows.len() as u64,
+        rows: rows.iter().map(|row| shape.values(row)).collect(),
+        row_xmins: rows.iter().map(|row| shape.xmin(row)).collect(),
+        columns: shape.columns.clone(),
+    })
+}
+
+/// Reads the row by key alone to show what changed underneath the grid.
+/// A row missing under a `ctid` key may have been updated rather than
+/// deleted, which can't be told apart without reloading.
+pub(crate) async fn fetch_conflict(
+    client: &Arc<Client>,
+    shape: &TableShape,
+    schema: &str,
+    table: &str,
+    key: &RowKey,
+) -> Result<RowConflict, String> {
+    let sql = format!(
+        "SELECT {} FROM {}.{} WHERE {}",
+        shape.output_list(),
+        quote_ident(schema),
+        quote_ident(table),
+        key_clause(key, 1)?
+    );
+    let stmt = prepare_change(client, &sql, &key.values).await?;
+    let row = client
+        .query_opt(&stmt, &as_params(&key.values))
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    Ok(match row {
+        Some(row) => RowConflict {
+            kind: ConflictKind::Modified,
+            current_values: Some(shape.values(&row)),
+            current_xmin: shape.xmin(&row),
+            columns: shape.columns.clone(),
+        },
+        None if key.uses_ctid() => return Err(ROW_MOVED_ERROR.to_string()),
+        None => RowConflict {
+            kind: ConflictKind::Deleted,
+            columns: shape.columns.clone(),
+            current_values: None,
+            current_xmin: None,
+        },
+    })
+}
+
+fn has_expectation(change: &RowChange) -> bool {
+    matches!(change, RowChange::Update { expected: Some(_), .. })
+}
+
+/// Updates and deletes must hit exactly one row; anything else means the
+/// grid's copy of the row is stale or the key isn't unique.
+fn check_affected(change: &RowChange, affected: u64) -> Result<(), String> {
+    let key = match change {
+        RowChange::Insert { .. } => return Ok(()),
+        RowChange::Update { key, .. } | RowChange::Delete { key } => key,
+    };
+    match affected {
+        1 => Ok(()),
+        0 if key.uses_ctid() => Err(ROW_MOVED_ERROR.to_string()),
+        0 => Err("Satır bulunamadı; başka bir oturum tarafından silinmiş olabilir".to_string()),
+        n => Err(format!("Anahtar benzersiz değil: {} satır etkilenirdi", n)),
+    }
+}
+
+/// Runs the batch in one transaction, in order, and rolls everything back
+/// on the first failing change.
+pub async fn apply_changes(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    changes: &[PendingChange],
+) -> Result<ApplyChangesResult, String> {
+    if changes.is_empty() {
+        return Err("Uygulanacak değişiklik yok".to_string());
+    }
+    let start = Instant::now();
+
+    let mut results: Vec<ChangeResult> = changes
+        .iter()
+        .enumerate()
+        .map(|(index, pending)| ChangeResult {
+            index,
+            id: pending.id.clone(),
+            status: ChangeStatus::Skipped,
+            affected_rows: 0,
+            error: None,
+            conflict: None,
+        })
+        .collect();
+
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
+
+    let mut failed_index = None;
+    for (index, pending) in changes.iter().enumerate() {
+        let outcome = match build_change_sql(schema, table, &pending.change) {
+            Ok((sql, values)) => execute_change(client, &sql, &values).await,
+            Err(e) => Err(e),
+        };
+        let outcome = match (outcome, &pending.change) {
+            // A conflict ends the batch, so the shape is read at most once
+            (Ok(0), RowChange::Update { key, .. }) if has_expectation(&pending.change) => {
+                let conflict = match table_shape(client, schema, table).await {
+                    Ok(shape) => fetch_conflict(client, &shape, schema, table, key).await,
+                    Err(e) => Err(e),
+                };
+                match conflict {
+                    Ok(conflict) => {
+                        results[index].conflict = Some(conflict);
+                        Err(CONFLICT_ERROR.to_string())
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            (Ok(affected), change) => check_affected(change, affected).map(|_| affected),
+            (Err(e), _) => Err(e),
+        };
+        match outcome {
+            Ok(affected) => {
+                results[index].status = ChangeStatus::Applied;
+                results[index].affected_rows = affected;
+            }
+            Err(e) => {
+                results[index].status = ChangeStatus::Failed;
+                results[index].error = Some(e);
+                failed_index = Some(index);
+                break;
+            }
+        }
+    }
+
+    let committed = if failed_index.is_some() {
+        client.execute("ROLLBACK", &[]).await.ok();
+        false
+    } else if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
+    } else {
+        true
+    };
+
+    Ok(ApplyChangesResult {
+        committed,
+        results,
+        failed_index,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn key(columns: &[&str], values: &[&str], types: &[&str]) -> RowKey {
+        RowKey {
+            columns: strings(columns),
+            values: values.iter().map(|v| Some(v.to_string())).collect(),
+            types: strings(types),
+        }
+    }
+
+    fn update(expected: RowExpectation) -> RowChange {
+        RowChange::Update {
+            key: key(&["id"], &["7"], &["bigint"]),
+            columns: strings(&["name", "doc"]),
+            values: vec![Some("Ada".to_string()), Some("{}".to_string())],
+            column_types: strings(&["text", "json"]),
+            expected: Some(expected),
+        }
+    }
+
+    #[test]
+    fn insert() {
+        let change = RowChange::Insert {
+            columns: strings(&["name", "age"]),
+            values: vec![Some("Ada".to_string()), None],
+            column_types: strings(&["text", "integer"]),
+        };
+        let (sql, params) = build_change_sql("public", "users", &change).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO \"public\".\"users\" (\"name\", \"age\") VALUES ($1::text, $2::integer)"
+        );
+        assert_eq!(params, vec![Some("Ada".to_string()), None]);
+    }
+
+    #[test]
+    fn insert_without_columns_is_rejected() {
+        let change = RowChange::Insert {
+            columns: Vec::new(),
+            values: Vec::new(),
+            column_types: Vec::new(),
+        };
+        assert!(build_change_sql("public", "users", &change).is_err());
+    }
+
+    #[test]
+    fn update_by_composite_key() {
+        let change = RowChange::Update {
+            key: key(&["org", "id"], &["1", "7"], &["integer", "bigint"]),
+            columns: strings(&["name"]),
+            values: vec![Some("Ada".to_string())],
+            column_types: strings(&["text"]),
+            expected: None,
+        };
+        let (sql, params) = build_change_sql("public", "users", &change).unwrap();
+        assert_eq!(
+            sql,
+            "UPDATE \"public\".\"users\" SET \"name\" = $1::text \
+             WHERE \"org\" = $2::integer AND \"id\" = $3::bigint"
+        );
+        assert_eq!(
+            params,
+            vec![Some("Ada".to_string()), Some("1".to_string()), Some("7".to_string())]
+        );
+    }
+
+    #[test]
+    fn update_with_mismatched_values_is_rejected() {
+        let change = RowChange::Update {
+            key: key(&["id"], &["7"], &["bigint"]),
+            columns: strings(&["name", "age"]),
+            values: vec![Some("Ada".to_string())],
+            column_types: strings(&["text", "integer"]),
+            expected: None,
+        };
+        assert!(build_change_sql("public", "users", &change).is_err());
+    }
+
+    #[test]
+    fn delete_by_ctid() {
+        let change = RowChange::Delete {
+            key: key(&["ctid"], &["(0,1)"], &["tid"]),
+        };
+        let (sql, params) = build_change_sql("public", "logs", &change).unwrap();
+        assert_eq!(sql, "DELETE FROM \"public\".\"logs\" WHERE \"ctid\" = $1::tid");
+        assert_eq!(params, vec![Some("(0,1)".to_string())]);
+    }
+
+    #[test]
+    fn delete_without_key_is_rejected() {
+        let change = RowChange::Delete {
+            key: key(&[], &[], &[]),
+        };
+        assert!(build_change_sql("public", "logs", &change).is_err());
+    }
+
+    #[test]
+    fn expectation_compares_original_values() {
+        let expected = RowExpectation {
+            original_values: Some(vec![Some("Bob".to_string()), None]),
+            xmin: None,
+        };
+        let (sql, params) = build_change_sql("public", "users", &update(expected)).unwrap();
+        assert_eq!(
+            sql,
+            "UPDATE \"public\".\"users\" SET \"name\" = $1::text, \"doc\" = $2::json \
+             WHERE \"id\" = $3::bigint AND \"name\" IS NOT DISTINCT FROM $4::text \
+             AND \"doc\"::jsonb IS NOT DISTINCT FROM $5::jsonb"
+        );
+        assert_eq!(params[3..], [Some("Bob".to_string()), None]);
+    }
+
+    #[test]
+    fn expectation_checks_xmin() {
+        let (conditions, values) = expectation_clause(
+            &RowExpectation {
+                original_values: None,
+                xmin: Some("1234".to_string()),
+            },
+            &strings(&["name"]),
+            &strings(&["text"]),
+            3,
+        )
+        .unwrap();
+        assert_eq!(conditions, vec!["xmin = $3::xid".to_string()]);
+        assert_eq!(values, vec![Some("1234".to_string())]);
+    }
+
+    #[test]
+    fn expectation_compares_xml_as_text() {
+        let (conditions, _) = expectation_clause(
+            &RowExpectation {
+                original_values: Some(vec![Some("<a/>".to_string())]),
+                xmin: Some("9".to_string()),
+            },
+            &strings(&["body"]),
+            &strings(&["XML"]),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            conditions,
+            vec![
+                "\"body\"::text IS NOT DISTINCT FROM $1".to_string(),
+                "xmin = $2::xid".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn expectation_with_wrong_value_count_is_rejected() {
+        let expected = RowExpectation {
+            original_values: Some(vec![Some("Bob".to_string())]),
+            xmin: None,
+        };
+        assert!(build_change_sql("public", "users", &update(expected)).is_err());
+    }
+}
@@ -10,6 +10,7 @@ use crate::ssh_tunnel::SshTunnel;
 
 pub struct ConnectionManager {
     connections: HashMap<String, Arc<Client>>,
+    configs: HashMap<String, ConnectionConfig>,
     ssh_tunnels: HashMap<String, SshTunnel>,
 }
 
@@ -17,6 +18,7 @@ impl ConnectionManager {
     pub fn new() -> Self {
         Self {
             connections: HashMap::new(),
+            configs: HashMap::new(),
             ssh_tunnels: HashMap::new(),
         }
     }
@@ -131,6 +133,7 @@ impl ConnectionManager {
         match Self::connect_pg(&conn_str, config.ssl_mode).await {
             Ok((client, _handle)) => {
                 self.connections.insert(config.id.clone(), Arc::new(client));
+                self.configs.insert(config.id.clone(), config.clone());
                 Ok(config.id.clone())
             }
             Err(e) => {
@@ -147,6 +150,7 @@ impl ConnectionManager {
 
     pub fn disconnect(&mut self, connection_id: &str) -> Result<(), String> {
         self.connections.remove(connection_id);
+        self.configs.remove(connection_id);
         // Shutdown SSH tunnel if exists
         if let Some(tunnel) = self.ssh_tunnels.remove(connection_id) {
             tunnel.shutdown();
@@ -161,6 +165,19 @@ impl ConnectionManager {
             .ok_or_else(|| format!("Bağlantı bulunamadı: {}", connection_id))
     }
 
+    /// Opens a separate session to the same server, reusing the SSH tunnel.
+    /// Used for work that must not share the main client (editor cursors,
+    /// maintenance, index builds, the activity monitor).
+    pub async fn open_session(&self, connection_id: &str) -> Result<Client, String> {
+        let config = self
+            .configs
+            .get(connection_id)
+            .ok_or_else(|| format!("Bağlantı bulunamadı: {}", connection_id))?;
+        let conn_str = Self::build_connection_string(config, self.get_tunnel_port(connection_id));
+        let (client, _handle) = Self::connect_pg(&conn_str, config.ssl_mode).await?;
+        Ok(client)
+    }
+
     pub fn get_tunnel_port(&self, connection_id: &str) -> Option<u16> {
         self.ssh_tunnels.get(connection_id).map(|t| t.local_port)
     }
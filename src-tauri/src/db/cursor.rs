@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, SimpleQueryMessage};
+
+use super::connection::ConnectionManager;
+use super::params::{bind_query, QueryParam};
+use super::queries::{self, format_db_error, pg_value_to_json, ExecuteQueryResult, TableColumnMeta};
+use super::value;
+
+// ── Server-side cursors for editor results ──────────────────────────────
+
+pub const DEFAULT_FETCH_SIZE: usize = 500;
+pub const DEFAULT_MAX_ROWS: usize = 100_000;
+
+/// A cursor left unread this long is closed, so its session doesn't hold a
+/// transaction (and snapshot) open on the server.
+pub const CURSOR_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// An open cursor on its own session; the session stays inside a
+/// transaction until the cursor is exhausted, closed or left idle.
+pub struct QueryCursor {
+    client: Client,
+    connection_id: String,
+    name: String,
+    fetched: usize,
+    max_rows: usize,
+    last_used: Instant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRowsBatch {
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// Total rows fetched from this result so far
+    pub row_count: usize,
+    pub has_more: bool,
+    pub truncated: bool,
+    pub execution_time_ms: u128,
+}
+
+struct FetchOutcome {
+    rows: Vec<Vec<serde_json::Value>>,
+    has_more: bool,
+    truncated: bool,
+}
+
+impl QueryCursor {
+    async fn fetch(&mut self, count: usize) -> Result<FetchOutcome, String> {
+        self.last_used = Instant::now();
+        let limit = count.max(1).min(self.max_rows - self.fetched);
+
+        let rows = self
+            .client
+            .query(&format!("FETCH FORWARD {} FROM {}", limit, self.name) as &str, &[])
+            .await
+            .map_err(|e| format_db_error(&e))?;
+
+        self.fetched += rows.len();
+
+        let json_rows: Vec<Vec<serde_json::Value>> = rows
+            .iter()
+            .map(|row| {
+                (0..row.len())
+                    .map(|idx| pg_value_to_json(row, idx))
+                    .collect()
+            })
+            .collect();
+
+        if rows.len() < limit {
+            return Ok(FetchOutcome { rows: json_rows, has_more: false, truncated: false });
+        }
+
+        if self.fetched >= self.max_rows {
+            // Row cap reached: check whether anything was left behind
+            let remaining = self
+                .client
+                .execute(&format!("MOVE FORWARD 1 FROM {}", self.name) as &str, &[])
+                .await
+                .map_err(|e| format_db_error(&e))?;
+            return Ok(FetchOutcome { rows: json_rows, has_more: false, truncated: remaining > 0 });
+        }
+
+        Ok(FetchOutcome { rows: json_rows, has_more: true, truncated: false })
+    }
+
+    /// Closes the cursor and ends the session's transaction, rolling it
+    /// back if the cursor can't be closed cleanly.
+    async fn close(&mut self) {
+        let closed = self
+            .client
+            .batch_execute(&format!("CLOSE {}; COMMIT", self.name))
+            .await
+            .is_ok();
+        if !closed {
+            self.client.batch_execute("ROLLBACK").await.ok();
+        }
+    }
+}
+
+pub struct CursorManager {
+    cursors: HashMap<String, Arc<Mutex<QueryCursor>>>,
+}
+
+impl CursorManager {
+    pub fn new() -> Self {
+        Self {
+            cursors: HashMap::new(),
+        }
+    }
+}
+
+pub type AppCursorManager = Arc<Mutex<CursorManager>>;
+
+pub fn create_cursor_manager() -> AppCursorManager {
+    Arc::new(Mutex::new(CursorManager::new()))
+}
+
+fn is_cursor_query(sql: &str) -> bool {
+    let upper = sql.trim().to_uppercase();
+    upper.starts_with("SELECT")
+        || upper.starts_with("WITH")
+        || upper.starts_with("TABLE")
+        || upper.starts_with("VALUES")
+}
+
+/// State of the main client a cursor session has to reproduce: whether a
+/// transaction block is open and the parameters changed with `SET`.
+struct MainSessionState {
+    in_transaction: bool,
+    settings: Vec<(String, String)>,
+}
+
+async fn main_session_state(client: &Client) -> Option<MainSessionState> {
+    // A simple-protocol statement outside a block starts its own
+    // transaction, so `now()` equals its timestamp; inside a block `now()`
+    // stays at the block's start
+    let probe = client
+        .simple_query("SELECT now() <> statement_timestamp()")
+        .await
+        .ok()?;
+    let in_transaction = probe.iter().any(|message| {
+        matches!(message, SimpleQueryMessage::Row(row) if row.get(0) == Some("t"))
+    });
+
+    let rows = client
+        .query(
+            "SELECT name, current_setting(name) FROM pg_settings WHERE source = 'session'",
+            &[],
+        )
+        .await
+        .ok()?;
+    Some(MainSessionState {
+        in_transaction,
+        settings: rows.iter().map(|row| (row.get(0), row.get(1))).collect(),
+    })
+}
+
+/// Opens a session for the cursor with the main client's `SET` parameters
+/// (search_path and the like) applied.
+async fn open_cursor_session(
+    manager: &ConnectionManager,
+    connection_id: &str,
+    settings: &[(String, String)],
+) -> Result<Client, String> {
+    let session = manager.open_session(connection_id).await?;
+    for (name, value) in settings {
+        // Parameters that can't be set here keep their defaults
+        session
+            .execute("SELECT set_config($1, $2, false)", &[name, value])
+            .await
+            .ok();
+    }
+    Ok(session)
+}
+
+/// Closes the cursor once it has sat unread for `CURSOR_IDLE_TIMEOUT`.
+fn spawn_idle_watchdog(cursors: AppCursorManager, handle: String) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(CURSOR_IDLE_TIMEOUT).await;
+            let cursor = match cursors.lock().await.cursors.get(&handle).cloned() {
+                Some(cursor) => cursor,
+                None => return,
+            };
+            let mut cursor = cursor.lock().await;
+            if cursor.last_used.elapsed() >= CURSOR_IDLE_TIMEOUT {
+                cursors.lock().await.cursors.remove(&handle);
+                cursor.close().await;
+                return;
+            }
+        }
+    });
+}
+
+/// Runs a query from the editor. Row-returning queries are read through a
+/// cursor on a dedicated session, so the cursor's transaction never holds up
+/// or absorbs the commands run on the main client: the first `fetch_size`
+/// rows are returned right away and the rest stay on the server behind
+/// `result_handle`. Queries that need the main client's own state (an open
+/// transaction, temp tables) run there as a plain query up to the row cap.
+pub async fn execute_query(
+    manager: &ConnectionManager,
+    cursors: &AppCursorManager,
+    connection_id: &str,
+    sql: &str,
+    params: &[QueryParam],
+    fetch_size: Option<usize>,
+    max_rows: Option<usize>,
+) -> Result<ExecuteQueryResult, String> {
+    let client = manager.get_client(connection_id)?;
+    let fetch_size = fetch_size.unwrap_or(DEFAULT_FETCH_SIZE).max(1);
+    let max_rows = max_rows.unwrap_or(DEFAULT_MAX_ROWS).max(1);
+
+    if !is_cursor_query(sql) {
+        return queries::execute_query(&client, sql, params, None).await;
+    }
+
+    let state = match main_session_state(&client).await {
+        Some(state) if !state.in_transaction => state,
+        // Inside a transaction (or an aborted one) the query has to see the
+        // user's uncommitted work, or report the real error
+        _ => return queries::execute_query(&client, sql, params, Some(max_rows)).await,
+    };
+
+    let start = Instant::now();
+    let trimmed = sql.trim().trim_end_matches(';').trim_end();
+
+    let bound = if params.is_empty() {
+        None
+    } else {
+        Some(bind_query(&client, trimmed, params).await?)
+    };
+    let (query_sql, param_types, values) = match &bound {
+        Some(b) => (b.sql.as_str(), b.param_types(), b.params()),
+        None => (trimmed, Vec::new(), Vec::new()),
+    };
+
+    let session = open_cursor_session(manager, connection_id, &state.settings).await?;
+
+    // Temp tables only exist on the main client; genuine errors are
+    // reported by the plain query as well
+    let stmt = match session.prepare_typed(query_sql, &param_types).await {
+        Ok(stmt) => stmt,
+        Err(_) => return queries::execute_query(&client, sql, params, Some(max_rows)).await,
+    };
+
+    let columns: Vec<TableColumnMeta> = stmt
+        .columns()
+        .iter()
+        .map(|col| TableColumnMeta {
+            name: col.name().to_string(),
+            data_type: col.type_().name().to_string(),
+        })
+        .collect();
+
+    // Undecodable columns are read as text through a wrapping query; if it
+    // can't be declared, the plain query below reports which columns failed
+    let wrapped = value::text_cast_query(query_sql, stmt.columns())?;
+    let query_sql = wrapped.as_deref().unwrap_or(query_sql);
+
+    let name = format!("dbleaf_cursor_{}", uuid::Uuid::new_v4().simple());
+    let declare_sql = format!("DECLARE {} NO SCROLL CURSOR FOR {}", name, query_sql);
+
+    session
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    let declared = match session.prepare_typed(&declare_sql, &param_types).await {
+        Ok(declare_stmt) => session.execute(&declare_stmt, &values).await.is_ok(),
+        Err(_) => false,
+    };
+    if !declared {
+        // Some statements can't run as a cursor (e.g. data-modifying CTEs);
+        // fall back to a plain query that only reads up to the row cap.
+        drop(session);
+        return queries::execute_query(&client, sql, params, Some(max_rows)).await;
+    }
+
+    let mut cursor = QueryCursor {
+        client: session,
+        connection_id: connection_id.to_string(),
+        name,
+        fetched: 0,
+        max_rows,
+        last_used: Instant::now(),
+    };
+
+    let outcome = match cursor.fetch(fetch_size).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            cursor.close().await;
+            return Err(e);
+        }
+    };
+
+    let result_handle = if outcome.has_more {
+        let handle = uuid::Uuid::new_v4().to_string();
+        cursors
+            .lock()
+            .await
+            .cursors
+            .insert(handle.clone(), Arc::new(Mutex::new(cursor)));
+        spawn_idle_watchdog(cursors.clone(), handle.clone());
+        Some(handle)
+    } else {
+        cursor.close().await;
+        None
+    };
+
+    Ok(ExecuteQueryResult {
+        columns,
+        row_count: outcome.rows.len(),
+        rows: outcome.rows,
+        execution_time_ms: start.elapsed().as_millis(),
+        is_select: true,
+        affected_rows: None,
+        result_handle,
+        has_more: outcome.has_more,
+        truncated: outcome.truncated,
+    })
+}
+
+pub async fn fetch_query_rows(
+    cursors: &AppCursorManager,
+    handle: &str,
+    count: Option<usize>,
+) -> Result<QueryRowsBatch, String> {
+    let start = Instant::now();
+    let cursor = cursors
+        .lock()
+        .await
+        .cursors
+        .get(handle)
+        .cloned()
+        .ok_or_else(|| format!("Sonuç kümesi bulunamadı: {}", handle))?;
+
+    let mut cursor = cursor.lock().await;
+    let outcome = cursor.fetch(count.unwrap_or(DEFAULT_FETCH_SIZE)).await;
+
+    let finished = !matches!(outcome, Ok(FetchOutcome { has_more: true, .. }));
+    if finished {
+        cursor.close().await;
+        cursors.lock().await.cursors.remove(handle);
+    }
+
+    let outcome = outcome?;
+    Ok(QueryRowsBatch {
+        rows: outcome.rows,
+        row_count: cursor.fetched,
+        has_more: outcome.has_more,
+        truncated: outcome.truncated,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
+
+pub async fn close_query_result(cursors: &AppCursorManager, handle: &str) -> Result<(), String> {
+    let cursor = cursors.lock().await.cursors.remove(handle);
+    if let Some(cursor) = cursor {
+        cursor.lock().await.close().await;
+    }
+    Ok(())
+}
+
+/// Closes every open result of a connection, e.g. when it disconnects.
+/// Locks are taken cursor-first, in the same order as fetches.
+pub async fn close_connection_cursors(cursors: &AppCursorManager, connection_id: &str) {
+    let open: Vec<(String, Arc<Mutex<QueryCursor>>)> = cursors
+        .lock()
+        .await
+        .cursors
+        .iter()
+        .map(|(handle, cursor)| (handle.clone(), cursor.clone()))
+        .collect();
+
+    for (handle, cursor) in open {
+        let mut cursor = cursor.lock().await;
+        if cursor.connection_id == connection_id {
+            cursors.lock().await.cursors.remove(&handle);
+            cursor.close().await;
+        }
+    }
+}
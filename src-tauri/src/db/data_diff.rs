@@ -0,0 +1,486 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident, quote_literal, table_key_info};
+
+// ── Table data comparison ───────────────────────────────────────────────
+
+pub const DEFAULT_MAX_DIFFERENCES: usize = 1000;
+const FETCH_SIZE: usize = 2000;
+const CURSOR_NAME: &str = "dbleaf_diff";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDiffOptions {
+    /// Differences kept in the result; counting continues past the cap
+    #[serde(default)]
+    pub max_differences: Option<usize>,
+    #[serde(default)]
+    pub generate_sql: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnDifference {
+    pub column: String,
+    pub source: Option<String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowDifference {
+    pub kind: String, // "missing_in_target", "missing_in_source" or "changed"
+    pub key: Vec<Option<String>>,
+    /// Changed columns only
+    pub changes: Vec<ColumnDifference>,
+    /// The whole row of the side that has it, for missing rows
+    pub values: Option<Vec<Option<String>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDiffResult {
+    pub key_columns: Vec<String>,
+    /// Columns present on both sides, in source order
+    pub columns: Vec<String>,
+    pub source_only_columns: Vec<String>,
+    pub target_only_columns: Vec<String>,
+    pub source_rows: i64,
+    pub target_rows: i64,
+    pub identical: i64,
+    pub changed: i64,
+    pub missing_in_target: i64,
+    pub missing_in_source: i64,
+    pub differences: Vec<RowDifference>,
+    /// More differences exist than `differences` holds
+    pub truncated: bool,
+    /// INSERT/UPDATE/DELETE statements that sync the target, for the kept differences
+    pub sync_sql: Option<String>,
+    pub execution_time_ms: u128,
+}
+
+struct ColumnMeta {
+    name: String,
+    type_name: String,
+    generated: bool,
+    identity_always: bool,
+}
+
+async fn column_meta(client: &Arc<Client>, qualified: &str) -> Result<Vec<ColumnMeta>, String> {
+    let rows = client
+        .query(
+            "SELECT a.attname, t.typname, a.attgenerated <> '', a.attidentity = 'a'
+             FROM pg_attribute a
+             JOIN pg_type t ON t.oid = a.atttypid
+             WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&qualified],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ColumnMeta {
+            name: row.get(0),
+            type_name: row.get(1),
+            generated: row.get(2),
+            identity_always: row.get(3),
+        })
+        .collect())
+}
+
+/// How a key column is ordered on the server and compared here. Both must
+/// agree for the merge to work.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyOrder {
+    /// Ordered by value, compared as integers
+    Integer,
+    /// Ordered bytewise (`COLLATE "C"` or a bytewise type), compared as strings
+    Bytewise,
+    /// Ordered by the text form in `COLLATE "C"`
+    Text,
+}
+
+impl KeyOrder {
+    fn for_type(type_name: &str) -> Self {
+        match type_name {
+            "int2" | "int4" | "int8" | "oid" => KeyOrder::Integer,
+            "text" | "varchar" | "name" | "uuid" => KeyOrder::Bytewise,
+            _ => KeyOrder::Text,
+        }
+    }
+
+    fn order_by(self, column: &str, type_name: &str) -> String {
+        let col = quote_ident(column);
+        match self {
+            KeyOrder::Integer => col,
+            KeyOrder::Bytewise if type_name == "uuid" => col,
+            KeyOrder::Bytewise => format!("{} COLLATE \"C\"", col),
+            KeyOrder::Text => format!("{}::text COLLATE \"C\"", col),
+        }
+    }
+
+    fn compare(self, a: &str, b: &str) -> Ordering {
+        if self == KeyOrder::Integer {
+            if let (Ok(a), Ok(b)) = (a.parse::<i64>(), b.parse::<i64>()) {
+                return a.cmp(&b);
+            }
+        }
+        a.as_bytes().cmp(b.as_bytes())
+    }
+}
+
+type DiffRow = Vec<Option<String>>;
+
+/// Rows of one side, read through a cursor in key order.
+struct RowStream {
+    client: Arc<Client>,
+    buffer: VecDeque<DiffRow>,
+    done: bool,
+    rows: i64,
+}
+
+impl RowStream {
+    async fn open(client: Arc<Client>, sql: &str) -> Result<Self, String> {
+        client
+            .batch_execute(&format!(
+                "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY;
+                 DECLARE {} NO SCROLL CURSOR FOR {}",
+                CURSOR_NAME, sql
+            ))
+            .await
+            .map_err(|e| format_db_error(&e))?;
+        Ok(Self {
+            client,
+            buffer: VecDeque::new(),
+            done: false,
+            rows: 0,
+        })
+    }
+
+    async fn peek(&mut self) -> Result<Option<&DiffRow>, String> {
+        if self.buffer.is_empty() && !self.done {
+            let rows = self
+                .client
+                .query(
+                    &format!("FETCH FORWARD {} FROM {}", FETCH_SIZE, CURSOR_NAME) as &str,
+                    &[],
+                )
+                .await
+                .map_err(|e| format_db_error(&e))?;
+            if rows.len() < FETCH_SIZE {
+                self.done = true;
+            }
+            for row in &rows {
+                self.buffer
+                    .push_back((0..row.len()).map(|i| row.get(i)).collect());
+            }
+        }
+        Ok(self.buffer.front())
+    }
+
+    fn take(&mut self) -> Option<DiffRow> {
+        self.rows += 1;
+        self.buffer.pop_front()
+    }
+
+    async fn close(&self) {
+        self.client.batch_execute("COMMIT").await.ok();
+    }
+}
+
+fn compare_keys(a: &DiffRow, b: &DiffRow, orders: &[KeyOrder]) -> Ordering {
+    for (i, order) in orders.iter().enumerate() {
+        // Primary key values are never NULL
+        let ord = order.compare(
+            a[i].as_deref().unwrap_or_default(),
+            b[i].as_deref().unwrap_or_default(),
+        );
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+fn literal(value: &Option<String>) -> String {
+    match value {
+        Some(v) => quote_literal(v),
+        None => "NULL".to_string(),
+    }
+}
+
+struct SyncSqlBuilder<'a> {
+    table: String,
+    key_columns: &'a [String],
+    columns: &'a [String],
+    /// Columns that can't be written (generated)
+    read_only: Vec<bool>,
+    overriding: bool,
+}
+
+impl SyncSqlBuilder<'_> {
+    fn key_condition(&self, key: &[Option<String>]) -> String {
+        self.key_columns
+            .iter()
+            .zip(key)
+            .map(|(c, v)| format!("{} = {}", quote_ident(c), literal(v)))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    fn statement(&self, diff: &RowDifference) -> String {
+        match diff.kind.as_str() {
+            "missing_in_target" => {
+                let values = diff.values.as_deref().unwrap_or_default();
+                let (cols, vals): (Vec<String>, Vec<String>) = self
+                    .columns
+                    .iter()
+                    .zip(values)
+                    .zip(&self.read_only)
+                    .filter(|(_, read_only)| !**read_only)
+                    .map(|((c, v), _)| (quote_ident(c), literal(v)))
+                    .unzip();
+                format!(
+                    "INSERT INTO {} ({}){} VALUES ({});",
+                    self.table,
+                    cols.join(", "),
+                    if self.overriding { " OVERRIDING SYSTEM VALUE" } else { "" },
+                    vals.join(", ")
+                )
+            }
+            "missing_in_source" => format!(
+                "DELETE FROM {} WHERE {};",
+                self.table,
+                self.key_condition(&diff.key)
+            ),
+            _ => {
+                let sets: Vec<String> = diff
+                    .changes
+                    .iter()
+                    .filter(|c| {
+                        let idx = self.columns.iter().position(|n| *n == c.column);
+                        !idx.map(|i| self.read_only[i]).unwrap_or(true)
+                    })
+                    .map(|c| format!("{} = {}", quote_ident(&c.column), literal(&c.source)))
+                    .collect();
+                if sets.is_empty() {
+                    return String::new();
+                }
+                format!(
+                    "UPDATE {} SET {} WHERE {};",
+                    self.table,
+                    sets.join(", "),
+                    self.key_condition(&diff.key)
+                )
+            }
+        }
+    }
+}
+
+/// Compares two tables with the same primary key by streaming both in key
+/// order and merging, so memory use doesn't grow with the table size.
+/// `source` and `target` are dedicated sessions, possibly on different servers.
+pub async fn compare_table_data(
+    source: Client,
+    source_schema: &str,
+    source_table: &str,
+    target: Client,
+    target_schema: &str,
+    target_table: &str,
+    options: &DataDiffOptions,
+) -> Result<DataDiffResult, String> {
+    let start = Instant::now();
+    let source = Arc::new(source);
+    let target = Arc::new(target);
+    let source_qualified = format!("{}.{}", quote_ident(source_schema), quote_ident(source_table));
+    let target_qualified = format!("{}.{}", quote_ident(target_schema), quote_ident(target_table));
+    let max_differences = options.max_differences.unwrap_or(DEFAULT_MAX_DIFFERENCES);
+
+    let (key_columns, _) = table_key_info(&source, &source_qualified).await?;
+    if key_columns.is_empty() {
+        return Err("Veri karşılaştırması için birincil anahtar gerekli".to_string());
+    }
+    let (target_keys, _) = table_key_info(&target, &target_qualified).await?;
+    if target_keys != key_columns {
+        return Err(format!(
+            "Tabloların birincil anahtarları eşleşmiyor: ({}) / ({})",
+            key_columns.join(", "),
+            target_keys.join(", ")
+        ));
+    }
+
+    let source_columns = column_meta(&source, &source_qualified).await?;
+    let target_columns = column_meta(&target, &target_qualified).await?;
+
+    let shared: Vec<(&ColumnMeta, &ColumnMeta)> = source_columns
+        .iter()
+        .filter_map(|s| target_columns.iter().find(|t| t.name == s.name).map(|t| (s, t)))
+        .collect();
+    let columns: Vec<String> = shared.iter().map(|(s, _)| s.name.clone()).collect();
+    let source_only_columns: Vec<String> = source_columns
+        .iter()
+        .filter(|c| !columns.contains(&c.name))
+        .map(|c| c.name.clone())
+        .collect();
+    let target_only_columns: Vec<String> = target_columns
+        .iter()
+        .filter(|c| !columns.contains(&c.name))
+        .map(|c| c.name.clone())
+        .collect();
+
+    let mut orders = Vec::new();
+    let mut order_by = Vec::new();
+    for key in &key_columns {
+        let (s, t) = shared
+            .iter()
+            .find(|(s, _)| s.name == *key)
+            .ok_or_else(|| format!("Anahtar kolonu bulunamadı: {}", key))?;
+        let order = if s.type_name == t.type_name {
+            KeyOrder::for_type(&s.type_name)
+        } else {
+            KeyOrder::Text
+        };
+        orders.push(order);
+        order_by.push(order.order_by(key, &s.type_name));
+    }
+
+    // Each row is the key columns followed by every shared column, as text
+    let select_list: Vec<String> = key_columns
+        .iter()
+        .chain(columns.iter())
+        .map(|c| format!("{}::text", quote_ident(c)))
+        .collect();
+    let scan_sql = |qualified: &str| {
+        format!(
+            "SELECT {} FROM {} ORDER BY {}",
+            select_list.join(", "),
+            qualified,
+            order_by.join(", ")
+        )
+    };
+
+    let mut source_rows = RowStream::open(source.clone(), &scan_sql(&source_qualified)).await?;
+    let mut target_rows = RowStream::open(target.clone(), &scan_sql(&target_qualified)).await?;
+
+    let key_len = key_columns.len();
+    let mut differences = Vec::new();
+    let (mut identical, mut changed, mut missing_in_target, mut missing_in_source) = (0, 0, 0, 0);
+
+    let record = |diff: RowDifference, differences: &mut Vec<RowDifference>| {
+        if differences.len() < max_differences {
+            differences.push(diff);
+        }
+    };
+
+    loop {
+        let ordering = {
+            let s = source_rows.peek().await?;
+            let t = target_rows.peek().await?;
+            match (s, t) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(s), Some(t)) => compare_keys(s, t, &orders),
+            }
+        };
+
+        match ordering {
+            Ordering::Less => {
+                let row = source_rows.take().unwrap_or_default();
+                missing_in_target += 1;
+                record(
+                    RowDifference {
+                        kind: "missing_in_target".to_string(),
+                        key: row[..key_len].to_vec(),
+                        changes: Vec::new(),
+                        values: Some(row[key_len..].to_vec()),
+                    },
+                    &mut differences,
+                );
+            }
+            Ordering::Greater => {
+                let row = target_rows.take().unwrap_or_default();
+                missing_in_source += 1;
+                record(
+                    RowDifference {
+                        kind: "missing_in_source".to_string(),
+                        key: row[..key_len].to_vec(),
+                        changes: Vec::new(),
+                        values: Some(row[key_len..].to_vec()),
+                    },
+                    &mut differences,
+                );
+            }
+            Ordering::Equal => {
+                let s = source_rows.take().unwrap_or_default();
+                let t = target_rows.take().unwrap_or_default();
+                let changes: Vec<ColumnDifference> = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| s[key_len + i] != t[key_len + i])
+                    .map(|(i, name)| ColumnDifference {
+                        column: name.clone(),
+                        source: s[key_len + i].clone(),
+                        target: t[key_len + i].clone(),
+                    })
+                    .collect();
+                if changes.is_empty() {
+                    identical += 1;
+                } else {
+                    changed += 1;
+                    record(
+                        RowDifference {
+                            kind: "changed".to_string(),
+                            key: s[..key_len].to_vec(),
+                            changes,
+                            values: None,
+                        },
+                        &mut differences,
+                    );
+                }
+            }
+        }
+    }
+
+    source_rows.close().await;
+    target_rows.close().await;
+
+    let total_differences = changed + missing_in_target + missing_in_source;
+    let sync_sql = if options.generate_sql {
+        let builder = SyncSqlBuilder {
+            table: target_qualified.clone(),
+            key_columns: &key_columns,
+            columns: &columns,
+            read_only: shared.iter().map(|(_, t)| t.generated).collect(),
+            overriding: shared.iter().any(|(_, t)| t.identity_always),
+        };
+        let statements: Vec<String> = differences
+            .iter()
+            .map(|d| builder.statement(d))
+            .filter(|s| !s.is_empty())
+            .collect();
+        Some(statements.join("\n"))
+    } else {
+        None
+    };
+
+    Ok(DataDiffResult {
+        key_columns,
+        columns,
+        source_only_columns,
+        target_only_columns,
+        source_rows: source_rows.rows,
+        target_rows: target_rows.rows,
+        identical,
+        changed,
+        missing_in_target,
+        missing_in_source,
+        truncated: total_differences as usize > differences.len(),
+        differences,
+        sync_sql,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident, quote_literal};
+
+// ── Databases ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDatabaseOptions {
+    pub name: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Sets both LC_COLLATE and LC_CTYPE
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub tablespace: Option<String>,
+    #[serde(default)]
+    pub connection_limit: Option<i32>,
+}
+
+fn ident(name: &str, what: &str) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} adı boş olamaz", what));
+    }
+    Ok(quote_ident(name))
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+pub fn build_create_database_sql(options: &CreateDatabaseOptions) -> Result<String, String> {
+    let mut sql = format!("CREATE DATABASE {}", ident(&options.name, "Veritabanı")?);
+    if let Some(owner) = non_empty(&options.owner) {
+        sql.push_str(&format!(" OWNER {}", quote_ident(owner)));
+    }
+    if let Some(template) = non_empty(&options.template) {
+        sql.push_str(&format!(" TEMPLATE {}", quote_ident(template)));
+    }
+    if let Some(encoding) = non_empty(&options.encoding) {
+        sql.push_str(&format!(" ENCODING {}", quote_literal(encoding)));
+    }
+    if let Some(locale) = non_empty(&options.locale) {
+        sql.push_str(&format!(
+            " LC_COLLATE {} LC_CTYPE {}",
+            quote_literal(locale),
+            quote_literal(locale)
+        ));
+    }
+    if let Some(tablespace) = non_empty(&options.tablespace) {
+        sql.push_str(&format!(" TABLESPACE {}", quote_ident(tablespace)));
+    }
+    if let Some(limit) = options.connection_limit {
+        sql.push_str(&format!(" CONNECTION LIMIT {}", limit));
+    }
+    Ok(sql)
+}
+
+/// CREATE/ALTER/DROP DATABASE can't run in a transaction, so each runs on its own.
+async fn run(client: &Arc<Client>, sql: String) -> Result<String, String> {
+    client
+        .batch_execute(&sql)
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(sql)
+}
+
+pub async fn create_database(
+    client: &Arc<Client>,
+    options: &CreateDatabaseOptions,
+) -> Result<String, String> {
+    let sql = build_create_database_sql(options)?;
+    run(client, sql).await
+}
+
+pub async fn rename_database(client: &Arc<Client>, name: &str, new_name: &str) -> Result<String, String> {
+    let sql = format!(
+        "ALTER DATABASE {} RENAME TO {}",
+        ident(name, "Veritabanı")?,
+        ident(new_name, "Yeni veritabanı")?
+    );
+    run(client, sql).await
+}
+
+/// `force` terminates other sessions on the database first (PG13+).
+pub async fn drop_database(client: &Arc<Client>, name: &str, force: bool) -> Result<String, String> {
+    let name = ident(name, "Veritabanı")?;
+    if force {
+        let version: i32 = client
+            .query_one("SELECT current_setting('server_version_num')::int", &[])
+            .await
+            .map_err(|e| format_db_error(&e))?
+            .get(0);
+        if version < 130000 {
+            return Err("FORCE seçeneği PostgreSQL 13 veya üzerini gerektirir".to_string());
+        }
+    }
+    let sql = format!(
+        "DROP DATABASE {}{}",
+        name,
+        if force { " WITH (FORCE)" } else { "" }
+    );
+    run(client, sql).await
+}
+
+// ── Schemas ─────────────────────────────────────────────────────────────
+
+pub async fn create_schema(
+    client: &Arc<Client>,
+    name: &str,
+    owner: Option<&str>,
+) -> Result<String, String> {
+    let mut sql = format!("CREATE SCHEMA {}", ident(name, "Şema")?);
+    if let Some(owner) = owner.map(str::trim).filter(|o| !o.is_empty()) {
+        sql.push_str(&format!(" AUTHORIZATION {}", quote_ident(owner)));
+    }
+    run(client, sql).await
+}
+
+pub async fn rename_schema(client: &Arc<Client>, name: &str, new_name: &str) -> Result<String, String> {
+    let sql = format!(
+        "ALTER SCHEMA {} RENAME TO {}",
+        ident(name, "Şema")?,
+        ident(new_name, "Yeni şema")?
+    );
+    run(client, sql).await
+}
+
+pub async fn drop_schema(client: &Arc<Client>, name: &str, cascade: bool) -> Result<String, String> {
+    let sql = format!(
+        "DROP SCHEMA {}{}",
+        ident(name, "Şema")?,
+        if cascade { " CASCADE" } else { "" }
+    );
+    run(client, sql).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependentObject {
+    /// e.g. "table", "view", "function", "index", "table constraint"
+    pub object_type: String,
+    pub schema: Option<String>,
+    pub name: Option<String>,
+    /// Human-readable description from `pg_describe_object`
+    pub description: String,
+    /// Lives in a different schema than the object being dropped
+    pub outside_schema: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDropPreview {
+    pub schema: String,
+    /// Everything `DROP SCHEMA ... CASCADE` would remove along with the schema
+    pub objects: Vec<DependentObject>,
+    pub requires_cascade: bool,
+    pub sql: String,
+}
+
+/// Walks `pg_depend` from an object to everything that depends on it,
+/// directly or through other dependents. Views show up as the view itself
+/// rather than their `_RETURN` rule.
+pub(crate) async fn dependent_objects(
+    client: &Arc<Client>,
+    catalog: &str,
+    oid: u32,
+    home_schema: &str,
+) -> Result<Vec<DependentObject>, String> {
+    let rows = client
+        .query(
+            "WITH RECURSIVE deps AS (
+                SELECT $1::text::regclass::oid as refclassid, $2::oid as refobjid
+                UNION
+                SELECT CASE WHEN d.classid = 'pg_rewrite'::regclass
+                            THEN 'pg_class'::regclass::oid ELSE d.classid END,
+                       CASE WHEN d.classid = 'pg_rewrite'::regclass
+                            THEN (SELECT r.ev_class FROM pg_rewrite r WHERE r.oid = d.objid)
+                            ELSE d.objid END
+                FROM pg_depend d
+                JOIN deps ON d.refclassid = deps.refclassid AND d.refobjid = deps.refobjid
+                WHERE d.deptype IN ('n', 'a')
+             )
+             SELECT DISTINCT o.type, o.schema, o.name,
+                    pg_describe_object(deps.refclassid, deps.refobjid, 0),
+                    COALESCE(o.schema <> $3, false)
+             FROM deps, pg_identify_object(deps.refclassid, deps.refobjid, 0) o
+             WHERE NOT (deps.refclassid = $1::text::regclass::oid AND deps.refobjid = $2)
+             ORDER BY 5 DESC, 1, 4",
+            &[&catalog, &oid, &home_schema],
+        )
+        .await
+        .map_err(|e| format!("Bağımlı nesneler alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DependentObject {
+            object_type: row.get(0),
+            schema: row.get(1),
+            name: row.get(2),
+            description: row.get(3),
+            outside_schema: row.get(4),
+        })
+        .collect())
+}
+
+pub async fn preview_drop_schema(client: &Arc<Client>, name: &str) -> Result<SchemaDropPreview, String> {
+    let oid: u32 = client
+        .query_opt("SELECT oid FROM pg_namespace WHERE nspname = $1", &[&name])
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .ok_or_else(|| format!("Şema bulunamadı: {}", name))?
+        .get(0);
+
+    let objects = dependent_objects(client, "pg_namespace", oid, name).await?;
+    let requires_cascade = !objects.is_empty();
+    Ok(SchemaDropPreview {
+        schema: name.to_string(),
+        sql: format!(
+            "DROP SCHEMA {}{}",
+            quote_ident(name),
+            if requires_cascade { " CASCADE" } else { "" }
+        ),
+        objects,
+        requires_cascade,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(name: &str) -> CreateDatabaseOptions {
+        CreateDatabaseOptions {
+            name: name.to_string(),
+            owner: None,
+            template: None,
+            encoding: None,
+            locale: None,
+            tablespace: None,
+            connection_limit: None,
+        }
+    }
+
+    #[test]
+    fn name_only() {
+        assert_eq!(
+            build_create_database_sql(&options("app")).unwrap(),
+            "CREATE DATABASE \"app\""
+        );
+    }
+
+    #[test]
+    fn empty_name_is_rejected() {
+        assert!(build_create_database_sql(&options("  ")).is_err());
+    }
+
+    #[test]
+    fn all_options() {
+        let options = CreateDatabaseOptions {
+            owner: Some("app_owner".to_string()),
+            template: Some("template0".to_string()),
+            encoding: Some("UTF8".to_string()),
+            locale: Some("tr_TR.UTF-8".to_string()),
+            tablespace: Some("fast".to_string()),
+            connection_limit: Some(20),
+            ..options("My \"App\"")
+        };
+        assert_eq!(
+            build_create_database_sql(&options).unwrap(),
+            "CREATE DATABASE \"My \"\"App\"\"\" OWNER \"app_owner\" TEMPLATE \"template0\" \
+             ENCODING 'UTF8' LC_COLLATE 'tr_TR.UTF-8' LC_CTYPE 'tr_TR.UTF-8' \
+             TABLESPACE \"fast\" CONNECTION LIMIT 20"
+        );
+    }
+
+    #[test]
+    fn blank_options_are_skipped() {
+        let options = CreateDatabaseOptions {
+            owner: Some(" ".to_string()),
+            encoding: Some(String::new()),
+            ..options("app")
+        };
+        assert_eq!(
+            build_create_database_sql(&options).unwrap(),
+            "CREATE DATABASE \"app\""
+        );
+    }
+}
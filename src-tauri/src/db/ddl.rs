@@ -0,0 +1,567 @@
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{quote_ident, quote_literal};
+
+// ── Table DDL ───────────────────────────────────────────────────────────
+
+fn ddl_error(e: tokio_postgres::Error) -> String {
+    format!("DDL oluşturulamadı: {}", e)
+}
+
+struct TableMeta {
+    oid: u32,
+    relkind: String,
+    unlogged: bool,
+    owner: String,
+    comment: Option<String>,
+    row_security: bool,
+    force_row_security: bool,
+    options: Option<String>,
+    tablespace: Option<String>,
+    partition_key: Option<String>,
+    partition_parent: Option<String>,
+    partition_bound: Option<String>,
+    parents: Vec<String>,
+}
+
+async fn table_meta(client: &Arc<Client>, schema: &str, table: &str) -> Result<TableMeta, String> {
+    let row = client
+        .query_opt(
+            "SELECT c.oid, c.relkind::text, c.relpersistence = 'u',
+                    pg_get_userbyid(c.relowner),
+                    obj_description(c.oid, 'pg_class'),
+                    c.relrowsecurity, c.relforcerowsecurity,
+                    array_to_string(c.reloptions, ', '),
+                    ts.spcname,
+                    CASE WHEN c.relkind = 'p' THEN pg_get_partkeydef(c.oid) END,
+                    (SELECT quote_ident(pn.nspname) || '.' || quote_ident(p.relname)
+                     FROM pg_inherits i
+                     JOIN pg_class p ON p.oid = i.inhparent
+                     JOIN pg_namespace pn ON pn.oid = p.relnamespace
+                     WHERE i.inhrelid = c.oid AND c.relispartition),
+                    CASE WHEN c.relispartition THEN pg_get_expr(c.relpartbound, c.oid) END,
+                    ARRAY(SELECT quote_ident(pn.nspname) || '.' || quote_ident(p.relname)
+                          FROM pg_inherits i
+                          JOIN pg_class p ON p.oid = i.inhparent
+                          JOIN pg_namespace pn ON pn.oid = p.relnamespace
+                          WHERE i.inhrelid = c.oid AND NOT c.relispartition
+                          ORDER BY i.inhseqno)
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             LEFT JOIN pg_tablespace ts ON ts.oid = c.reltablespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&schema, &table],
+        )
+        .await
+        .map_err(ddl_error)?
+        .ok_or_else(|| format!("Tablo bulunamadı: {}.{}", schema, table))?;
+
+    Ok(TableMeta {
+        oid: row.get(0),
+        relkind: row.get(1),
+        unlogged: row.get(2),
+        owner: row.get(3),
+        comment: row.get(4),
+        row_security: row.get(5),
+        force_row_security: row.get(6),
+        options: row.get(7),
+        tablespace: row.get(8),
+        partition_key: row.get(9),
+        partition_parent: row.get(10),
+        partition_bound: row.get(11),
+        parents: row.get(12),
+    })
+}
+
+/// Sequences backing serial columns; they must exist before the table.
+async fn owned_sequences(
+    client: &Arc<Client>,
+    oid: u32,
+    qualified: &str,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let rows = client
+        .query(
+            "SELECT quote_ident(sn.nspname) || '.' || quote_ident(sc.relname),
+                    a.attname,
+                    format_type(s.seqtypid, NULL),
+                    s.seqstart, s.seqincrement, s.seqmin, s.seqmax, s.seqcache, s.seqcycle
+             FROM pg_depend d
+             JOIN pg_class sc ON sc.oid = d.objid AND sc.relkind = 'S'
+             JOIN pg_namespace sn ON sn.oid = sc.relnamespace
+             JOIN pg_sequence s ON s.seqrelid = sc.oid
+             JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+             WHERE d.classid = 'pg_class'::regclass
+                AND d.refclassid = 'pg_class'::regclass
+                AND d.refobjid = $1
+                AND d.deptype = 'a'
+             ORDER BY a.attnum",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    let mut creates = Vec::new();
+    let mut owned_by = Vec::new();
+    for row in &rows {
+        let name: String = row.get(0);
+        let column: String = row.get(1);
+        let data_type: String = row.get(2);
+        let cycle: bool = row.get(8);
+        creates.push(format!(
+            "CREATE SEQUENCE {} AS {} START WITH {} INCREMENT BY {} MINVALUE {} MAXVALUE {} CACHE {}{};",
+            name,
+            data_type,
+            row.get::<_, i64>(3),
+            row.get::<_, i64>(4),
+            row.get::<_, i64>(5),
+            row.get::<_, i64>(6),
+            row.get::<_, i64>(7),
+            if cycle { " CYCLE" } else { " NO CYCLE" }
+        ));
+        owned_by.push(format!(
+            "ALTER SEQUENCE {} OWNED BY {}.{};",
+            name,
+            qualified,
+            quote_ident(&column)
+        ));
+    }
+    Ok((creates, owned_by))
+}
+
+struct ColumnDdl {
+    definition: String,
+    name: String,
+    comment: Option<String>,
+}
+
+async fn column_definitions(client: &Arc<Client>, oid: u32) -> Result<Vec<ColumnDdl>, String> {
+    let rows = client
+        .query(
+            "SELECT a.attname,
+                    format_type(a.atttypid, a.atttypmod),
+                    a.attnotnull,
+                    pg_get_expr(ad.adbin, ad.adrelid),
+                    a.attidentity::text,
+                    a.attgenerated::text,
+                    CASE WHEN a.attcollation <> 0 AND a.attcollation <> t.typcollation
+                         THEN quote_ident(cn.nspname) || '.' || quote_ident(co.collname) END,
+                    col_description(a.attrelid, a.attnum),
+                    s.seqstart,
+                    s.seqincrement
+             FROM pg_attribute a
+             JOIN pg_type t ON t.oid = a.atttypid
+             LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+             LEFT JOIN pg_collation co ON co.oid = a.attcollation
+             LEFT JOIN pg_namespace cn ON cn.oid = co.collnamespace
+             LEFT JOIN pg_depend dep ON dep.refobjid = a.attrelid
+                AND dep.refobjsubid = a.attnum
+                AND dep.classid = 'pg_class'::regclass
+                AND dep.deptype = 'i'
+             LEFT JOIN pg_sequence s ON s.seqrelid = dep.objid
+             WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped AND a.attislocal
+             ORDER BY a.attnum",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let name: String = row.get(0);
+            let data_type: String = row.get(1);
+            let not_null: bool = row.get(2);
+            let default: Option<String> = row.get(3);
+            let identity: String = row.get(4);
+            let generated: String = row.get(5);
+            let collation: Option<String> = row.get(6);
+            let seq_start: Option<i64> = row.get(8);
+            let seq_increment: Option<i64> = row.get(9);
+
+            let mut definition = format!("{} {}", quote_ident(&name), data_type);
+            if let Some(collation) = collation {
+                definition.push_str(&format!(" COLLATE {}", collation));
+            }
+            if !identity.is_empty() {
+                let kind = if identity == "a" { "ALWAYS" } else { "BY DEFAULT" };
+                definition.push_str(&format!(" GENERATED {} AS IDENTITY", kind));
+                if let (Some(start), Some(increment)) = (seq_start, seq_increment) {
+                    definition.push_str(&format!(
+                        " (START WITH {} INCREMENT BY {})",
+                        start, increment
+                    ));
+                }
+            } else if let Some(expr) = default {
+                if generated == "s" {
+                    definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expr));
+                } else {
+                    definition.push_str(&format!(" DEFAULT {}", expr));
+                }
+            }
+            if not_null {
+                definition.push_str(" NOT NULL");
+            }
+
+            ColumnDdl {
+                definition,
+                name,
+                comment: row.get(7),
+            }
+        })
+        .collect())
+}
+
+/// Builds a runnable script that recreates the table: sequences, columns,
+/// constraints, indexes, triggers, RLS policies, comments, owner, grants
+/// and partitions.
+pub async fn get_table_ddl(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+) -> Result<String, String> {
+    let meta = table_meta(client, schema, table).await?;
+    if meta.relkind != "r" && meta.relkind != "p" {
+        return Err("DDL yalnızca tablolar için oluşturulabilir".to_string());
+    }
+    let oid = meta.oid;
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+
+    let mut script: Vec<String> = vec![format!("-- Table: {}", qualified)];
+
+    let (sequences, sequence_owners) = owned_sequences(client, oid, &qualified).await?;
+    if !sequences.is_empty() {
+        script.push(sequences.join("\n"));
+    }
+
+    // Constraints (FKs are added after the table, like pg_dump does)
+    let constraint_rows = client
+        .query(
+            "SELECT conname, contype::text, pg_get_constraintdef(oid, true),
+                    obj_description(oid, 'pg_constraint')
+             FROM pg_constraint
+             WHERE conrelid = $1 AND conislocal AND contype IN ('p', 'u', 'c', 'f', 'x')
+             ORDER BY CASE contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'c' THEN 2
+                                   WHEN 'x' THEN 3 ELSE 4 END, conname",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    let mut inline_constraints = Vec::new();
+    let mut after_table = Vec::new();
+    let mut comments = Vec::new();
+    for row in &constraint_rows {
+        let name: String = row.get(0);
+        let kind: String = row.get(1);
+        let definition: String = row.get(2);
+        let constraint = format!("CONSTRAINT {} {}", quote_ident(&name), definition);
+        if kind == "f" || meta.partition_parent.is_some() {
+            after_table.push(format!("ALTER TABLE {} ADD {};", qualified, constraint));
+        } else {
+            inline_constraints.push(constraint);
+        }
+        if let Some(comment) = row.get::<_, Option<String>>(3) {
+            comments.push(format!(
+                "COMMENT ON CONSTRAINT {} ON {} IS {};",
+                quote_ident(&name),
+                qualified,
+                quote_literal(&comment)
+            ));
+        }
+    }
+
+    // CREATE TABLE
+    let columns = column_definitions(client, oid).await?;
+    let mut create = format!(
+        "CREATE {}TABLE {}",
+        if meta.unlogged { "UNLOGGED " } else { "" },
+        qualified
+    );
+    match (&meta.partition_parent, &meta.partition_bound) {
+        (Some(parent), Some(bound)) => {
+            create.push_str(&format!(" PARTITION OF {}\n{}", parent, bound));
+        }
+        _ => {
+            let body: Vec<String> = columns
+                .iter()
+                .map(|c| c.definition.clone())
+                .chain(inline_constraints)
+                .map(|line| format!("    {}", line))
+                .collect();
+            if body.is_empty() {
+                create.push_str(" ()");
+            } else {
+                create.push_str(&format!(" (\n{}\n)", body.join(",\n")));
+            }
+            if !meta.parents.is_empty() {
+                create.push_str(&format!("\nINHERITS ({})", meta.parents.join(", ")));
+            }
+        }
+    }
+    if let Some(key) = &meta.partition_key {
+        create.push_str(&format!("\nPARTITION BY {}", key));
+    }
+    if let Some(options) = &meta.options {
+        create.push_str(&format!("\nWITH ({})", options));
+    }
+    if let Some(tablespace) = &meta.tablespace {
+        create.push_str(&format!("\nTABLESPACE {}", quote_ident(tablespace)));
+    }
+    create.push(';');
+    script.push(create);
+
+    after_table.extend(sequence_owners);
+    if !after_table.is_empty() {
+        script.push(after_table.join("\n"));
+    }
+
+    // Indexes not backing a constraint
+    let index_rows = client
+        .query(
+            "SELECT pg_get_indexdef(i.indexrelid),
+                    quote_ident(n.nspname) || '.' || quote_ident(ic.relname),
+                    obj_description(i.indexrelid, 'pg_class')
+             FROM pg_index i
+             JOIN pg_class ic ON ic.oid = i.indexrelid
+             JOIN pg_namespace n ON n.oid = ic.relnamespace
+             WHERE i.indrelid = $1
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_constraint c
+                    WHERE c.conindid = i.indexrelid
+                        AND c.conrelid = i.indrelid
+                        AND c.contype IN ('p', 'u', 'x'))
+                AND NOT EXISTS (SELECT 1 FROM pg_inherits inh WHERE inh.inhrelid = i.indexrelid)
+             ORDER BY ic.relname",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    if !index_rows.is_empty() {
+        let indexes: Vec<String> = index_rows
+            .iter()
+            .map(|row| format!("{};", row.get::<_, String>(0)))
+            .collect();
+        script.push(indexes.join("\n"));
+    }
+    for row in &index_rows {
+        if let Some(comment) = row.get::<_, Option<String>>(2) {
+            comments.push(format!(
+                "COMMENT ON INDEX {} IS {};",
+                row.get::<_, String>(1),
+                quote_literal(&comment)
+            ));
+        }
+    }
+
+    // Triggers; clones of a partitioned parent's triggers are skipped (PG13+)
+    let version: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .await
+        .map_err(ddl_error)?
+        .get(0);
+    let trigger_sql = format!(
+        "SELECT pg_get_triggerdef(oid, true), tgname, tgenabled::text,
+                obj_description(oid, 'pg_trigger')
+         FROM pg_trigger
+         WHERE tgrelid = $1 AND NOT tgisinternal{}
+         ORDER BY tgname",
+        if version >= 130000 { " AND tgparentid = 0" } else { "" }
+    );
+    let trigger_rows = client
+        .query(&trigger_sql, &[&oid])
+        .await
+        .map_err(ddl_error)?;
+
+    let mut triggers = Vec::new();
+    for row in &trigger_rows {
+        let name: String = row.get(1);
+        let enabled: String = row.get(2);
+        triggers.push(format!("{};", row.get::<_, String>(0)));
+        let state = match enabled.as_str() {
+            "D" => Some("DISABLE"),
+            "R" => Some("ENABLE REPLICA"),
+            "A" => Some("ENABLE ALWAYS"),
+            _ => None,
+        };
+        if let Some(state) = state {
+            triggers.push(format!(
+                "ALTER TABLE {} {} TRIGGER {};",
+                qualified,
+                state,
+                quote_ident(&name)
+            ));
+        }
+        if let Some(comment) = row.get::<_, Option<String>>(3) {
+            comments.push(format!(
+                "COMMENT ON TRIGGER {} ON {} IS {};",
+                quote_ident(&name),
+                qualified,
+                quote_literal(&comment)
+            ));
+        }
+    }
+    if !triggers.is_empty() {
+        script.push(triggers.join("\n"));
+    }
+
+    // Row level security
+    let mut security = Vec::new();
+    if meta.row_security {
+        security.push(format!("ALTER TABLE {} ENABLE ROW LEVEL SECURITY;", qualified));
+    }
+    if meta.force_row_security {
+        security.push(format!("ALTER TABLE {} FORCE ROW LEVEL SECURITY;", qualified));
+    }
+    let policy_rows = client
+        .query(
+            "SELECT p.polname, p.polpermissive, p.polcmd::text,
+                    ARRAY(SELECT CASE WHEN r = 0 THEN 'PUBLIC'
+                                      ELSE quote_ident(pg_get_userbyid(r)) END
+                          FROM unnest(p.polroles) r),
+                    pg_get_expr(p.polqual, p.polrelid),
+                    pg_get_expr(p.polwithcheck, p.polrelid)
+             FROM pg_policy p
+             WHERE p.polrelid = $1
+             ORDER BY p.polname",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    for row in &policy_rows {
+        let name: String = row.get(0);
+        let permissive: bool = row.get(1);
+        let command: String = row.get(2);
+        let roles: Vec<String> = row.get(3);
+        let using: Option<String> = row.get(4);
+        let with_check: Option<String> = row.get(5);
+
+        let command = match command.as_str() {
+            "r" => "SELECT",
+            "a" => "INSERT",
+            "w" => "UPDATE",
+            "d" => "DELETE",
+            _ => "ALL",
+        };
+        let mut policy = format!(
+            "CREATE POLICY {} ON {} AS {} FOR {} TO {}",
+            quote_ident(&name),
+            qualified,
+            if permissive { "PERMISSIVE" } else { "RESTRICTIVE" },
+            command,
+            roles.join(", ")
+        );
+        if let Some(using) = using {
+            policy.push_str(&format!(" USING ({})", using));
+        }
+        if let Some(with_check) = with_check {
+            policy.push_str(&format!(" WITH CHECK ({})", with_check));
+        }
+        policy.push(';');
+        security.push(policy);
+    }
+    if !security.is_empty() {
+        script.push(security.join("\n"));
+    }
+
+    // Comments
+    let mut table_comments = Vec::new();
+    if let Some(comment) = &meta.comment {
+        table_comments.push(format!(
+            "COMMENT ON TABLE {} IS {};",
+            qualified,
+            quote_literal(comment)
+        ));
+    }
+    for column in &columns {
+        if let Some(comment) = &column.comment {
+            table_comments.push(format!(
+                "COMMENT ON COLUMN {}.{} IS {};",
+                qualified,
+                quote_ident(&column.name),
+                quote_literal(comment)
+            ));
+        }
+    }
+    table_comments.extend(comments);
+    if !table_comments.is_empty() {
+        script.push(table_comments.join("\n"));
+    }
+
+    // Ownership and grants (table-level, then column-level)
+    let grant_rows = client
+        .query(
+            "SELECT CASE WHEN a.grantee = 0 THEN 'PUBLIC'
+                         ELSE quote_ident(pg_get_userbyid(a.grantee)) END,
+                    string_agg(a.privilege_type, ', ' ORDER BY a.privilege_type),
+                    a.is_grantable
+             FROM pg_class c, aclexplode(c.relacl) a
+             WHERE c.oid = $1 AND a.grantee <> c.relowner
+             GROUP BY a.grantee, a.is_grantable
+             UNION ALL
+             SELECT CASE WHEN a.grantee = 0 THEN 'PUBLIC'
+                         ELSE quote_ident(pg_get_userbyid(a.grantee)) END,
+                    string_agg(a.privilege_type || ' (' || quote_ident(att.attname) || ')', ', '
+                               ORDER BY att.attnum, a.privilege_type),
+                    a.is_grantable
+             FROM pg_attribute att, aclexplode(att.attacl) a
+             WHERE att.attrelid = $1 AND att.attnum > 0 AND NOT att.attisdropped
+             GROUP BY a.grantee, a.is_grantable",
+            &[&oid],
+        )
+        .await
+        .map_err(ddl_error)?;
+
+    let mut access = vec![format!(
+        "ALTER TABLE {} OWNER TO {};",
+        qualified,
+        quote_ident(&meta.owner)
+    )];
+    for row in &grant_rows {
+        let grantable: bool = row.get(2);
+        access.push(format!(
+            "GRANT {} ON {} TO {}{};",
+            row.get::<_, String>(1),
+            qualified,
+            row.get::<_, String>(0),
+            if grantable { " WITH GRANT OPTION" } else { "" }
+        ));
+    }
+    script.push(access.join("\n"));
+
+    // Partitions
+    if meta.relkind == "p" {
+        let partition_rows = client
+            .query(
+                "SELECT quote_ident(n.nspname) || '.' || quote_ident(c.relname),
+                        pg_get_expr(c.relpartbound, c.oid)
+                 FROM pg_inherits i
+                 JOIN pg_class c ON c.oid = i.inhrelid
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 WHERE i.inhparent = $1 AND c.relispartition
+                 ORDER BY n.nspname, c.relname",
+                &[&oid],
+            )
+            .await
+            .map_err(ddl_error)?;
+
+        if !partition_rows.is_empty() {
+            let partitions: Vec<String> = partition_rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "CREATE TABLE {} PARTITION OF {}\n{};",
+                        row.get::<_, String>(0),
+                        qualified,
+                        row.get::<_, String>(1)
+                    )
+                })
+                .collect();
+            script.push(partitions.join("\n"));
+        }
+    }
+
+    Ok(format!("{}\n", script.join("\n\n")))
+}
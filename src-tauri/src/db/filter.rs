@@ -0,0 +1,592 @@
+use serde::{Deserialize, Serialize};
+use tokio_postgres::types::{Kind, ToSql, Type};
+use tokio_postgres::Column;
+
+use super::params::type_cast_name;
+use super::queries::quote_ident;
+
+// ── Table data filtering & sorting ──────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperator {
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+    Ilike,
+    In,
+    IsNull,
+    IsNotNull,
+    Between,
+    /// jsonb containment (`@>`), optionally below `path`
+    JsonContains,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterCondition {
+    pub column: String,
+    pub operator: FilterOperator,
+    #[serde(default)]
+    pub value: Option<String>,
+    /// IN list, or the two bounds of BETWEEN
+    #[serde(default)]
+    pub values: Vec<Option<String>>,
+    /// JSON path for `json_contains`, e.g. ["address", "city"]
+    #[serde(default)]
+    pub path: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterLogic {
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterGroup {
+    pub logic: FilterLogic,
+    pub filters: Vec<FilterNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterNode {
+    Condition(FilterCondition),
+    Group(FilterGroup),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortSpec {
+    pub column: String,
+    pub direction: SortDirection,
+}
+
+/// Positional parameters collected while building a clause. Every value
+/// is sent as text (or text[]) and cast to the column type in SQL.
+#[derive(Default)]
+pub struct SqlParams {
+    values: Vec<Box<dyn ToSql + Sync + Send>>,
+    types: Vec<Type>,
+}
+
+impl SqlParams {
+    pub fn push_text(&mut self, value: Option<String>) -> String {
+        self.values.push(Box::new(value));
+        self.types.push(Type::TEXT);
+        format!("${}", self.values.len())
+    }
+
+    pub fn push_text_array(&mut self, values: Vec<Option<String>>) -> String {
+        self.values.push(Box::new(values));
+        self.types.push(Type::TEXT_ARRAY);
+        format!("${}", self.values.len())
+    }
+
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+
+    pub fn refs(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.values
+            .iter()
+            .map(|v| v.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+/// Name and type of a column the builders can refer to.
+pub trait ColumnInfo {
+    fn name(&self) -> &str;
+    fn type_(&self) -> &Type;
+}
+
+impl ColumnInfo for Column {
+    fn name(&self) -> &str {
+        Column::name(self)
+    }
+
+    fn type_(&self) -> &Type {
+        Column::type_(self)
+    }
+}
+
+pub(crate) fn find_column<'a, C: ColumnInfo>(columns: &'a [C], name: &str) -> Result<&'a C, String> {
+    columns
+        .iter()
+        .find(|c| c.name() == name)
+        .ok_or_else(|| format!("Kolon bulunamadı: {}", name))
+}
+
+fn condition_sql<C: ColumnInfo>(
+    cond: &FilterCondition,
+    columns: &[C],
+    params: &mut SqlParams,
+) -> Result<String, String> {
+    let column = find_column(columns, &cond.column)?;
+    let col = quote_ident(column.name());
+    let ty = type_cast_name(column.type_());
+
+    let comparison = |op: &str, params: &mut SqlParams| {
+        let p = params.push_text(cond.value.clone());
+        format!("{} {} ({}::text::{})", col, op, p, ty)
+    };
+
+    Ok(match cond.operator {
+        FilterOperator::Eq => comparison("=", params),
+        FilterOperator::Neq => comparison("<>", params),
+        FilterOperator::Lt => comparison("<", params),
+        FilterOperator::Lte => comparison("<=", params),
+        FilterOperator::Gt => comparison(">", params),
+        FilterOperator::Gte => comparison(">=", params),
+        FilterOperator::Like | FilterOperator::Ilike => {
+            let op = if cond.operator == FilterOperator::Like { "LIKE" } else { "ILIKE" };
+            let p = params.push_text(cond.value.clone());
+            format!("{}::text {} {}", col, op, p)
+        }
+        FilterOperator::In => {
+            // `= ANY` over an array column would compare arrays to elements
+            if matches!(column.type_().kind(), Kind::Array(_)) {
+                return Err(format!("IN filtresi dizi kolonlarında kullanılamaz: {}", cond.column));
+            }
+            if cond.values.is_empty() {
+                "FALSE".to_string()
+            } else {
+                let p = params.push_text_array(cond.values.clone());
+                format!("{} = ANY({}::text[]::{}[])", col, p, ty)
+            }
+        }
+        FilterOperator::IsNull => format!("{} IS NULL", col),
+        FilterOperator::IsNotNull => format!("{} IS NOT NULL", col),
+        FilterOperator::Between => {
+            if cond.values.len() != 2 {
+                return Err(format!("BETWEEN için iki değer gerekli: {}", cond.column));
+            }
+            let low = params.push_text(cond.values[0].clone());
+            let high = params.push_text(cond.values[1].clone());
+            format!(
+                "{} BETWEEN ({}::text::{}) AND ({}::text::{})",
+                col, low, ty, high, ty
+            )
+        }
+        FilterOperator::JsonContains => {
+            let p = params.push_text(cond.value.clone());
+            if cond.path.is_empty() {
+                format!("{}::jsonb @> {}::text::jsonb", col, p)
+            } else {
+                let path = params.push_text_array(cond.path.iter().cloned().map(Some).collect());
+                format!("({}::jsonb #> {}::text[]) @> {}::text::jsonb", col, path, p)
+            }
+        }
+    })
+}
+
+fn group_sql<C: ColumnInfo>(
+    group: &FilterGroup,
+    columns: &[C],
+    params: &mut SqlParams,
+) -> Result<Option<String>, String> {
+    let mut parts = Vec::new();
+    for node in &group.filters {
+        match node {
+            FilterNode::Condition(cond) => parts.push(condition_sql(cond, columns, params)?),
+            FilterNode::Group(inner) => {
+                if let Some(sql) = group_sql(inner, columns, params)? {
+                    parts.push(sql);
+                }
+            }
+        }
+    }
+
+    if parts.is_empty() {
+        return Ok(None);
+    }
+    let joiner = match group.logic {
+        FilterLogic::And => " AND ",
+        FilterLogic::Or => " OR ",
+    };
+    Ok(Some(format!("({})", parts.join(joiner))))
+}
+
+/// Builds the condition for a filter tree, without the `WHERE` keyword.
+/// Column names are checked against the table's columns.
+pub fn build_filter_sql<C: ColumnInfo>(
+    filters: Option<&FilterGroup>,
+    columns: &[C],
+    params: &mut SqlParams,
+) -> Result<Option<String>, String> {
+    match filters {
+        Some(group) => group_sql(group, columns, params),
+        None => Ok(None),
+    }
+}
+
+pub fn build_order_by<C: ColumnInfo>(sort: &[SortSpec], columns: &[C]) -> Result<String, String> {
+    if sort.is_empty() {
+        return Ok(String::new());
+    }
+    let parts = sort
+        .iter()
+        .map(|s| {
+            let column = find_column(columns, &s.column)?;
+            let direction = match s.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            Ok(format!("{} {} NULLS LAST", quote_ident(column.name()), direction))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("ORDER BY {}", parts.join(", ")))
+}
+
+// ── Keyset pagination ───────────────────────────────────────────────────
+
+/// A column of the keyset, in ORDER BY order. The last columns always form
+/// a unique key, which may be a system column such as `ctid`.
+pub struct KeyColumn {
+    pub name: String,
+    /// Type the cursor values are cast to, e.g. `int4` or `tid`
+    pub cast: String,
+    pub direction: SortDirection,
+    pub not_null: bool,
+}
+
+pub fn build_keyset_order_by(keys: &[KeyColumn]) -> String {
+    let parts: Vec<String> = keys
+        .iter()
+        .map(|k| {
+            let direction = match k.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            format!("{} {} NULLS LAST", quote_ident(&k.name), direction)
+        })
+        .collect();
+    format!("ORDER BY {}", parts.join(", "))
+}
+
+/// Builds the condition that selects rows strictly after `after` in the
+/// order given by `keys` (always NULLS LAST). All-ascending, non-null keys
+/// use a row comparison so an index on the key can be used.
+pub fn build_keyset_predicate(
+    keys: &[KeyColumn],
+    after: &[Option<String>],
+    params: &mut SqlParams,
+) -> Result<String, String> {
+    if keys.len() != after.len() {
+        return Err(format!(
+            "Sayfa imleci {} değer içermeli, {} verildi",
+            keys.len(),
+            after.len()
+        ));
+    }
+
+    let values: Vec<Option<String>> = keys
+        .iter()
+        .zip(after.iter())
+        .map(|(k, v)| {
+            v.as_ref().map(|v| {
+                let p = params.push_text(Some(v.clone()));
+                format!("({}::text::{})", p, k.cast)
+            })
+        })
+        .collect();
+
+    let simple = keys
+        .iter()
+        .all(|k| k.not_null && k.direction == SortDirection::Asc)
+        && values.iter().all(|v| v.is_some());
+    if simple {
+        let cols: Vec<String> = keys.iter().map(|k| quote_ident(&k.name)).collect();
+        let vals: Vec<String> = values.into_iter().flatten().collect();
+        return Ok(format!("({}) > ({})", cols.join(", "), vals.join(", ")));
+    }
+
+    // (k1 after v1) OR (k1 = v1 AND k2 after v2) OR ...
+    let mut branches = Vec::new();
+    for i in 0..keys.len() {
+        let mut terms: Vec<String> = keys[..i]
+            .iter()
+            .zip(values.iter())
+            .map(|(k, v)| {
+                let col = quote_ident(&k.name);
+                match v {
+                    Some(v) => format!("{} = {}", col, v),
+                    None => format!("{} IS NULL", col),
+                }
+            })
+            .collect();
+
+        let key = &keys[i];
+        let col = quote_ident(&key.name);
+        let after_term = match &values[i] {
+            // Nothing sorts after NULL except other NULLs, which are ties
+            None => continue,
+            Some(v) => {
+                let op = if key.direction == SortDirection::Asc { ">" } else { "<" };
+                if key.not_null {
+                    format!("{} {} {}", col, op, v)
+                } else {
+                    format!("({} {} {} OR {} IS NULL)", col, op, v, col)
+                }
+            }
+        };
+        terms.push(after_term);
+        branches.push(format!("({})", terms.join(" AND ")));
+    }
+
+    if branches.is_empty() {
+        return Ok("FALSE".to_string());
+    }
+    Ok(format!("({})", branches.join(" OR ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestColumn(&'static str, Type);
+
+    impl ColumnInfo for TestColumn {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn type_(&self) -> &Type {
+            &self.1
+        }
+    }
+
+    fn columns() -> Vec<TestColumn> {
+        vec![
+            TestColumn("id", Type::INT4),
+            TestColumn("name", Type::TEXT),
+            TestColumn("Data", Type::JSONB),
+            TestColumn("tags", Type::INT4_ARRAY),
+        ]
+    }
+
+    fn cond(column: &str, operator: FilterOperator) -> FilterCondition {
+        FilterCondition {
+            column: column.to_string(),
+            operator,
+            value: Some("1".to_string()),
+            values: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    fn group(logic: FilterLogic, filters: Vec<FilterNode>) -> FilterGroup {
+        FilterGroup { logic, filters }
+    }
+
+    fn build(filters: &FilterGroup) -> (Result<Option<String>, String>, usize) {
+        let mut params = SqlParams::default();
+        let sql = build_filter_sql(Some(filters), &columns(), &mut params);
+        (sql, params.types().len())
+    }
+
+    #[test]
+    fn no_filters_means_no_condition() {
+        let mut params = SqlParams::default();
+        assert_eq!(build_filter_sql::<TestColumn>(None, &[], &mut params), Ok(None));
+        let (sql, _) = build(&group(FilterLogic::And, Vec::new()));
+        assert_eq!(sql, Ok(None));
+    }
+
+    #[test]
+    fn comparisons_cast_the_parameter_to_the_column_type() {
+        let (sql, count) = build(&group(
+            FilterLogic::And,
+            vec![
+                FilterNode::Condition(cond("id", FilterOperator::Gte)),
+                FilterNode::Condition(cond("name", FilterOperator::Ilike)),
+            ],
+        ));
+        assert_eq!(
+            sql.unwrap().unwrap(),
+            "(\"id\" >= ($1::text::int4) AND \"name\"::text ILIKE $2)"
+        );
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn nested_groups_keep_their_logic() {
+        let inner = group(
+            FilterLogic::Or,
+            vec![
+                FilterNode::Condition(cond("id", FilterOperator::IsNull)),
+                FilterNode::Condition(cond("name", FilterOperator::Eq)),
+            ],
+        );
+        let (sql, _) = build(&group(
+            FilterLogic::And,
+            vec![
+                FilterNode::Condition(cond("id", FilterOperator::IsNotNull)),
+                FilterNode::Group(inner),
+                FilterNode::Group(group(FilterLogic::And, Vec::new())),
+            ],
+        ));
+        assert_eq!(
+            sql.unwrap().unwrap(),
+            "(\"id\" IS NOT NULL AND (\"id\" IS NULL OR \"name\" = ($1::text::text)))"
+        );
+    }
+
+    #[test]
+    fn in_lists_and_between() {
+        let mut empty_in = cond("id", FilterOperator::In);
+        empty_in.values = Vec::new();
+        let mut in_list = cond("id", FilterOperator::In);
+        in_list.values = vec![Some("1".to_string()), None];
+        let mut between = cond("id", FilterOperator::Between);
+        between.values = vec![Some("1".to_string()), Some("9".to_string())];
+
+        let (sql, count) = build(&group(
+            FilterLogic::And,
+            vec![
+                FilterNode::Condition(empty_in),
+                FilterNode::Condition(in_list),
+                FilterNode::Condition(between),
+            ],
+        ));
+        assert_eq!(
+            sql.unwrap().unwrap(),
+            "(FALSE AND \"id\" = ANY($1::text[]::int4[]) AND \
+             \"id\" BETWEEN ($2::text::int4) AND ($3::text::int4))"
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn in_is_rejected_on_array_columns() {
+        let mut in_list = cond("tags", FilterOperator::In);
+        in_list.values = vec![Some("{1,2}".to_string())];
+        let (sql, _) = build(&group(FilterLogic::And, vec![FilterNode::Condition(in_list)]));
+        assert!(sql.is_err());
+    }
+
+    #[test]
+    fn between_needs_two_values() {
+        let (sql, _) = build(&group(
+            FilterLogic::And,
+            vec![FilterNode::Condition(cond("id", FilterOperator::Between))],
+        ));
+        assert!(sql.is_err());
+    }
+
+    #[test]
+    fn json_contains_with_and_without_path() {
+        let plain = cond("Data", FilterOperator::JsonContains);
+        let mut nested = cond("Data", FilterOperator::JsonContains);
+        nested.path = vec!["address".to_string(), "city".to_string()];
+
+        let (sql, _) = build(&group(
+            FilterLogic::Or,
+            vec![FilterNode::Condition(plain), FilterNode::Condition(nested)],
+        ));
+        assert_eq!(
+            sql.unwrap().unwrap(),
+            "(\"Data\"::jsonb @> $1::text::jsonb OR (\"Data\"::jsonb #> $3::text[]) @> $2::text::jsonb)"
+        );
+    }
+
+    #[test]
+    fn unknown_columns_are_rejected() {
+        let (sql, _) = build(&group(
+            FilterLogic::And,
+            vec![FilterNode::Condition(cond("id\"; DROP TABLE t; --", FilterOperator::Eq))],
+        ));
+        assert!(sql.is_err());
+        let sort = vec![SortSpec { column: "missing".to_string(), direction: SortDirection::Asc }];
+        assert!(build_order_by(&sort, &columns()).is_err());
+    }
+
+    #[test]
+    fn order_by_puts_nulls_last() {
+        assert_eq!(build_order_by(&[], &columns()), Ok(String::new()));
+        let sort = vec![
+            SortSpec { column: "name".to_string(), direction: SortDirection::Desc },
+            SortSpec { column: "id".to_string(), direction: SortDirection::Asc },
+        ];
+        assert_eq!(
+            build_order_by(&sort, &columns()).unwrap(),
+            "ORDER BY \"name\" DESC NULLS LAST, \"id\" ASC NULLS LAST"
+        );
+    }
+
+    fn key(name: &str, cast: &str, direction: SortDirection, not_null: bool) -> KeyColumn {
+        KeyColumn {
+            name: name.to_string(),
+            cast: cast.to_string(),
+            direction,
+            not_null,
+        }
+    }
+
+    fn v(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn ascending_not_null_keys_use_a_row_comparison() {
+        let keys = vec![
+            key("id", "int4", SortDirection::Asc, true),
+            key("ctid", "tid", SortDirection::Asc, true),
+        ];
+        let mut params = SqlParams::default();
+        assert_eq!(
+            build_keyset_predicate(&keys, &[v("5"), v("(0,3)")], &mut params).unwrap(),
+            "(\"id\", \"ctid\") > (($1::text::int4), ($2::text::tid))"
+        );
+        assert_eq!(
+            build_keyset_order_by(&keys),
+            "ORDER BY \"id\" ASC NULLS LAST, \"ctid\" ASC NULLS LAST"
+        );
+    }
+
+    #[test]
+    fn mixed_directions_and_nullable_keys_expand_into_branches() {
+        let keys = vec![
+            key("name", "text", SortDirection::Desc, false),
+            key("id", "int4", SortDirection::Asc, true),
+        ];
+        let mut params = SqlParams::default();
+        assert_eq!(
+            build_keyset_predicate(&keys, &[v("bob"), v("7")], &mut params).unwrap(),
+            "(((\"name\" < ($1::text::text) OR \"name\" IS NULL)) OR \
+             (\"name\" = ($1::text::text) AND \"id\" > ($2::text::int4)))"
+        );
+    }
+
+    #[test]
+    fn null_cursor_value_only_continues_among_nulls() {
+        let keys = vec![
+            key("name", "text", SortDirection::Asc, false),
+            key("id", "int4", SortDirection::Asc, true),
+        ];
+        let mut params = SqlParams::default();
+        assert_eq!(
+            build_keyset_predicate(&keys, &[None, v("7")], &mut params).unwrap(),
+            "((\"name\" IS NULL AND \"id\" > ($1::text::int4)))"
+        );
+    }
+
+    #[test]
+    fn cursor_must_match_the_key_length() {
+        let keys = vec![key("id", "int4", SortDirection::Asc, true)];
+        let mut params = SqlParams::default();
+        assert!(build_keyset_predicate(&keys, &[], &mut params).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_postgres::error::ErrorPosition;
+use tokio_postgres::Client;
+
+use super::queries::format_db_error;
+
+// ── Function / procedure source ─────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionSource {
+    pub schema: String,
+    pub name: String,
+    pub argument_types: String,
+    pub kind: String, // "function" or "procedure"
+    pub return_type: String,
+    pub is_trigger: bool,
+    pub language: String,
+    pub volatility: String, // "immutable", "stable" or "volatile"
+    pub security_definer: bool,
+    pub owner: String,
+    /// Complete `CREATE OR REPLACE` statement
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCompileError {
+    pub message: String,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    /// 1-based line in the submitted statement
+    pub line: Option<usize>,
+    /// 1-based line inside the function body
+    pub body_line: Option<usize>,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDeployResult {
+    pub success: bool,
+    pub error: Option<FunctionCompileError>,
+    pub execution_time_ms: u128,
+}
+
+/// `argument_types` is the identity argument list as returned by `list_functions`.
+pub async fn get_function_source(
+    client: &Arc<Client>,
+    schema: &str,
+    name: &str,
+    argument_types: &str,
+) -> Result<FunctionSource, String> {
+    let row = client
+        .query_opt(
+            "SELECT n.nspname, p.proname, pg_get_function_identity_arguments(p.oid),
+                    p.prokind::text,
+                    COALESCE(pg_get_function_result(p.oid), ''),
+                    p.prorettype = 'trigger'::regtype OR p.prorettype = 'event_trigger'::regtype,
+                    l.lanname,
+                    CASE p.provolatile WHEN 'i' THEN 'immutable' WHEN 's' THEN 'stable' ELSE 'volatile' END,
+                    p.prosecdef,
+                    pg_get_userbyid(p.proowner),
+                    CASE WHEN p.prokind IN ('f', 'p') THEN pg_get_functiondef(p.oid) END
+             FROM pg_proc p
+             JOIN pg_namespace n ON n.oid = p.pronamespace
+             JOIN pg_language l ON l.oid = p.prolang
+             WHERE n.nspname = $1
+                AND p.proname = $2
+                AND pg_get_function_identity_arguments(p.oid) = $3",
+            &[&schema, &name, &argument_types],
+        )
+        .await
+        .map_err(|e| format!("Fonksiyon kaynağı alınamadı: {}", e))?
+        .ok_or_else(|| format!("Fonksiyon bulunamadı: {}.{}({})", schema, name, argument_types))?;
+
+    let kind: String = row.get(3);
+    let definition: Option<String> = row.get(10);
+    let definition = definition.ok_or_else(|| {
+        "Aggregate ve window fonksiyonlarının kaynağı gösterilemez".to_string()
+    })?;
+
+    Ok(FunctionSource {
+        schema: row.get(0),
+        name: row.get(1),
+        argument_types: row.get(2),
+        kind: if kind == "p" { "procedure" } else { "function" }.to_string(),
+        return_type: row.get(4),
+        is_trigger: row.get(5),
+        language: row.get(6),
+        volatility: row.get(7),
+        security_definer: row.get(8),
+        owner: row.get(9),
+        definition,
+    })
+}
+
+fn line_of(text: &str, char_pos: usize) -> usize {
+    text.chars().take(char_pos.saturating_sub(1)).filter(|c| *c == '\n').count() + 1
+}
+
+/// Line of the opening `$tag$` of the body; body line 1 starts right after it.
+fn body_start_line(sql: &str) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'$' {
+            let mut end = i + 1;
+            while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+                end += 1;
+            }
+            let tag_ok = end < bytes.len()
+                && bytes[end] == b'$'
+                && (end == i + 1 || !bytes[i + 1].is_ascii_digit());
+            if tag_ok {
+                return Some(sql[..i].matches('\n').count() + 1);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Finds the body line in a PL/pgSQL error context, e.g.
+/// `PL/pgSQL function f() line 3 at RETURN` or
+/// `compilation of PL/pgSQL function "f" near line 3`. Other context lines
+/// (such as quoted SQL statements) are ignored.
+fn context_line(context: &str) -> Option<usize> {
+    context.lines().find_map(|l| {
+        let l = l.trim();
+        if !l.starts_with("PL/pgSQL function ") && !l.starts_with("compilation of PL/pgSQL function ") {
+            return None;
+        }
+        let pos = l.rfind(" line ")? + " line ".len();
+        let digits: String = l[pos..].chars().take_while(|c| c.is_ascii_digit()).collect();
+        let rest = &l[pos + digits.len()..];
+        if rest.is_empty() || rest.starts_with(" at ") {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+fn compile_error(e: &tokio_postgres::Error, sql: &str) -> Option<FunctionCompileError> {
+    let db_err = e.as_db_error()?;
+    let body_start = body_start_line(sql);
+
+    let (line, body_line) = match db_err.position() {
+        Some(ErrorPosition::Original(pos)) => {
+            let line = line_of(sql, *pos as usize);
+            let body_line = body_start.filter(|start| line >= *start).map(|start| line - start + 1);
+            (Some(line), body_line)
+        }
+        // SQL-language bodies are parsed as an internal query
+        Some(ErrorPosition::Internal { position, query }) => {
+            let body_line = line_of(query, *position as usize);
+            (body_start.map(|start| start + body_line - 1), Some(body_line))
+        }
+        None => {
+            let body_line = db_err.where_().and_then(context_line);
+            (
+                body_start.zip(body_line).map(|(start, l)| start + l - 1),
+                body_line,
+            )
+        }
+    };
+
+    Some(FunctionCompileError {
+        message: db_err.message().to_string(),
+        detail: db_err.detail().map(|s| s.to_string()),
+        hint: db_err.hint().map(|s| s.to_string()),
+        line,
+        body_line,
+        code: db_err.code().code().to_string(),
+    })
+}
+
+/// Runs an edited `CREATE [OR REPLACE] FUNCTION/PROCEDURE` in a transaction.
+/// It is sent as a single extended-protocol statement, so the server rejects
+/// anything appended after it. Compile errors come back in the result with
+/// their line numbers.
+pub async fn deploy_function(client: &Arc<Client>, sql: &str) -> Result<FunctionDeployResult, String> {
+    let start = Instant::now();
+    let upper = sql.trim_start().to_uppercase();
+    let header: Vec<&str> = upper.split_whitespace().take(4).collect();
+    let is_create = header.first() == Some(&"CREATE")
+        && header
+            .iter()
+            .any(|w| *w == "FUNCTION" || *w == "PROCEDURE");
+    if !is_create {
+        return Err("Yalnızca CREATE FUNCTION / PROCEDURE ifadeleri dağıtılabilir".to_string());
+    }
+
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
+
+    if let Err(e) = client.execute(sql, &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return match compile_error(&e, sql) {
+            Some(error) => Ok(FunctionDeployResult {
+                success: false,
+                error: Some(error),
+                execution_time_ms: start.elapsed().as_millis(),
+            }),
+            None => Err(format_db_error(&e)),
+        };
+    }
+
+    if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
+    }
+
+    Ok(FunctionDeployResult {
+        success: true,
+        error: None,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_line_reads_plpgsql_frames() {
+        assert_eq!(context_line("PL/pgSQL function f() line 3 at RETURN"), Some(3));
+        assert_eq!(
+            context_line("compilation of PL/pgSQL function \"f\" near line 12"),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn context_line_ignores_other_frames() {
+        let context = "SQL statement \"SELECT 'line 7'\"\nPL/pgSQL function g(integer) line 4 at PERFORM";
+        assert_eq!(context_line(context), Some(4));
+        assert_eq!(context_line("SQL statement \"SELECT 'line 7'\""), None);
+        assert_eq!(context_line("PL/pgSQL function line_count() line x"), None);
+    }
+
+    #[test]
+    fn body_start_line_finds_dollar_quote() {
+        let sql = "CREATE FUNCTION f()\nRETURNS int\nLANGUAGE sql AS $body$\nSELECT 1\n$body$";
+        assert_eq!(body_start_line(sql), Some(3));
+        assert_eq!(body_start_line("CREATE FUNCTION f() RETURNS int AS $$ SELECT 1 $$"), Some(1));
+        assert_eq!(body_start_line("CREATE FUNCTION f(int) RETURNS int AS 'SELECT $1'"), None);
+    }
+}
@@ -0,0 +1,528 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tokio_postgres::Client;
+
+use super::maintenance::CancellableOperation;
+use super::queries::{format_db_error, quote_ident};
+
+// ── Index management ────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexColumnSpec {
+    /// Column name; ignored when `expression` is set
+    #[serde(default)]
+    pub column: Option<String>,
+    /// SQL expression, e.g. "lower(email)"
+    #[serde(default)]
+    pub expression: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateIndexOptions {
+    /// Generated by the server when omitted
+    #[serde(default)]
+    pub name: Option<String>,
+    pub columns: Vec<IndexColumnSpec>,
+    /// Access method, e.g. "btree", "gin", "brin"
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub unique: bool,
+    #[serde(default)]
+    pub concurrently: bool,
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// WHERE clause of a partial index
+    #[serde(default)]
+    pub predicate: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexBuildProgress {
+    pub phase: String,
+    pub blocks_total: i64,
+    pub blocks_done: i64,
+    pub tuples_total: i64,
+    pub tuples_done: i64,
+    pub lockers_total: i64,
+    pub lockers_done: i64,
+    pub partitions_total: i64,
+    pub partitions_done: i64,
+    /// Progress of the current phase, 0-100
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateIndexResult {
+    pub sql: String,
+    /// Set when `cancel_maintenance` stopped the build; no index is left behind
+    pub cancelled: bool,
+    pub execution_time_ms: u128,
+}
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn build_create_index_sql(
+    schema: &str,
+    table: &str,
+    options: &CreateIndexOptions,
+) -> Result<String, String> {
+    if options.columns.is_empty() {
+        return Err("İndeks için en az bir kolon gerekli".to_string());
+    }
+
+    let columns = options
+        .columns
+        .iter()
+        .map(|c| {
+            let target = match (&c.expression, &c.column) {
+                (Some(expr), _) if !expr.trim().is_empty() => format!("({})", expr.trim()),
+                (_, Some(column)) if !column.is_empty() => quote_ident(column),
+                _ => return Err("İndeks kolonu veya ifadesi boş olamaz".to_string()),
+            };
+            Ok(if c.descending {
+                format!("{} DESC", target)
+            } else {
+                target
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut sql = format!(
+        "CREATE {}INDEX {}",
+        if options.unique { "UNIQUE " } else { "" },
+        if options.concurrently { "CONCURRENTLY " } else { "" }
+    );
+    if let Some(name) = options.name.as_ref().filter(|n| !n.trim().is_empty()) {
+        sql.push_str(&format!("{} ", quote_ident(name)));
+    }
+    sql.push_str(&format!("ON {}.{}", quote_ident(schema), quote_ident(table)));
+    if let Some(method) = options.method.as_ref().filter(|m| !m.trim().is_empty()) {
+        sql.push_str(&format!(" USING {}", quote_ident(method.trim())));
+    }
+    sql.push_str(&format!(" ({})", columns.join(", ")));
+    if !options.include.is_empty() {
+        let include: Vec<String> = options.include.iter().map(|c| quote_ident(c)).collect();
+        sql.push_str(&format!(" INCLUDE ({})", include.join(", ")));
+    }
+    if let Some(predicate) = options.predicate.as_ref().filter(|p| !p.trim().is_empty()) {
+        sql.push_str(&format!(" WHERE {}", predicate.trim()));
+    }
+    Ok(sql)
+}
+
+async fn build_progress(client: &Arc<Client>, pid: i32) -> Option<IndexBuildProgress> {
+    let row = client
+        .query_opt(
+            "SELECT phase, blocks_total, blocks_done, tuples_total, tuples_done,
+                    lockers_total, lockers_done, partitions_total, partitions_done
+             FROM pg_stat_progress_create_index
+             WHERE pid = $1",
+            &[&pid],
+        )
+        .await
+        .ok()??;
+
+    let blocks_total: i64 = row.get(1);
+    let blocks_done: i64 = row.get(2);
+    let tuples_total: i64 = row.get(3);
+    let tuples_done: i64 = row.get(4);
+    let percent = if tuples_total > 0 {
+        Some(tuples_done as f64 * 100.0 / tuples_total as f64)
+    } else if blocks_total > 0 {
+        Some(blocks_done as f64 * 100.0 / blocks_total as f64)
+    } else {
+        None
+    };
+
+    Some(IndexBuildProgress {
+        phase: row.get(0),
+        blocks_total,
+        blocks_done,
+        tuples_total,
+        tuples_done,
+        lockers_total: row.get(5),
+        lockers_done: row.get(6),
+        partitions_total: row.get(7),
+        partitions_done: row.get(8),
+        percent,
+    })
+}
+
+/// Indexes of the table that are not valid, i.e. left behind by a
+/// concurrent build that did not finish.
+async fn invalid_indexes(client: &Arc<Client>, qualified: &str) -> Result<Vec<String>, String> {
+    let rows = client
+        .query(
+            "SELECT format('%I.%I', n.nspname, c.relname)
+             FROM pg_index i
+             JOIN pg_class c ON c.oid = i.indexrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE i.indrelid = $1::text::regclass AND NOT i.indisvalid",
+            &[&qualified],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Builds the index on its own session so the main connection stays
+/// usable; progress is polled from `pg_stat_progress_create_index` (PG12+).
+/// The build can be stopped with `cancel_maintenance` and `operation_id`;
+/// a cancelled concurrent build drops the invalid index it leaves behind.
+pub async fn create_index(
+    client: &Arc<Client>,
+    session: Client,
+    operation: CancellableOperation<'_>,
+    schema: &str,
+    table: &str,
+    options: &CreateIndexOptions,
+    channel: Channel<IndexBuildProgress>,
+) -> Result<CreateIndexResult, String> {
+    let sql = build_create_index_sql(schema, table, options)?;
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    let start = Instant::now();
+
+    let invalid_before = if options.concurrently {
+        invalid_indexes(client, &qualified).await?
+    } else {
+        Vec::new()
+    };
+
+    let pid: i32 = session
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    operation.register(pid).await;
+
+    let result = {
+        let build = session.batch_execute(&sql);
+        tokio::pin!(build);
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+
+        loop {
+            tokio::select! {
+                res = &mut build => break res,
+                _ = ticker.tick() => {
+                    if let Some(progress) = build_progress(client, pid).await {
+                        channel.send(progress).ok();
+                    }
+                }
+            }
+        }
+    };
+
+    // A cancel that arrives after the build finished changes nothing
+    let cancelled = operation.finish().await && result.is_err();
+    if let Err(e) = result {
+        if !cancelled {
+            return Err(format_db_error(&e));
+        }
+        if options.concurrently {
+            for index in invalid_indexes(client, &qualified).await? {
+                if !invalid_before.contains(&index) {
+                    session
+                        .batch_execute(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", index))
+                        .await
+                        .map_err(|e| format_db_error(&e))?;
+                }
+            }
+        }
+    }
+
+    Ok(CreateIndexResult {
+        sql,
+        cancelled,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
+
+pub async fn drop_index(
+    client: &Arc<Client>,
+    schema: &str,
+    name: &str,
+    concurrently: bool,
+    cascade: bool,
+) -> Result<String, String> {
+    if concurrently && cascade {
+        return Err("CONCURRENTLY ve CASCADE birlikte kullanılamaz".to_string());
+    }
+    let sql = format!(
+        "DROP INDEX {}{}.{}{}",
+        if concurrently { "CONCURRENTLY " } else { "" },
+        quote_ident(schema),
+        quote_ident(name),
+        if cascade { " CASCADE" } else { "" }
+    );
+    client
+        .batch_execute(&sql)
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(sql)
+}
+
+// ── Index health ────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexHealthIssue {
+    pub kind: String, // "unused", "duplicate", "overlapping", "invalid" or "bloat"
+    pub schema: String,
+    pub table_name: String,
+    pub index_name: String,
+    pub size_bytes: i64,
+    pub size: String,
+    pub detail: String,
+    /// Statement that would fix the issue
+    pub suggestion: String,
+}
+
+/// Only bloat above both thresholds is reported
+const BLOAT_MIN_RATIO: f64 = 0.3;
+const BLOAT_MIN_BYTES: i64 = 1024 * 1024;
+
+fn issue(
+    kind: &str,
+    row: &tokio_postgres::Row,
+    detail: String,
+    suggestion: String,
+) -> IndexHealthIssue {
+    IndexHealthIssue {
+        kind: kind.to_string(),
+        schema: row.get(0),
+        table_name: row.get(1),
+        index_name: row.get(2),
+        size_bytes: row.get(3),
+        size: row.get(4),
+        detail,
+        suggestion,
+    }
+}
+
+fn qualified_index(row: &tokio_postgres::Row) -> String {
+    format!(
+        "{}.{}",
+        quote_ident(&row.get::<_, String>(0)),
+        quote_ident(&row.get::<_, String>(2))
+    )
+}
+
+/// Flags unused, duplicate/overlapping, invalid and bloated indexes. Every
+/// query returns schema, table, index, size in bytes and pretty size first.
+pub async fn get_index_health(
+    client: &Arc<Client>,
+    schema: Option<&str>,
+) -> Result<Vec<IndexHealthIssue>, String> {
+    let map_err = |e: tokio_postgres::Error| format!("İndeks sağlığı alınamadı: {}", e);
+    let mut issues = Vec::new();
+
+    // Unused: never scanned and not enforcing a constraint
+    let rows = client
+        .query(
+            "SELECT s.schemaname::text, s.relname::text, s.indexrelname::text,
+                    pg_relation_size(s.indexrelid), pg_size_pretty(pg_relation_size(s.indexrelid))
+             FROM pg_stat_user_indexes s
+             JOIN pg_index i ON i.indexrelid = s.indexrelid
+             WHERE s.idx_scan = 0
+                AND NOT i.indisunique
+                AND NOT i.indisprimary
+                AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = s.indexrelid)
+                AND ($1::text IS NULL OR s.schemaname = $1)
+             ORDER BY pg_relation_size(s.indexrelid) DESC",
+            &[&schema],
+        )
+        .await
+        .map_err(map_err)?;
+    for row in &rows {
+        issues.push(issue(
+            "unused",
+            row,
+            "İstatistikler sıfırlandığından beri hiç kullanılmamış".to_string(),
+            format!("DROP INDEX CONCURRENTLY {};", qualified_index(row)),
+        ));
+    }
+
+    // Invalid: usually left behind by a failed CREATE INDEX CONCURRENTLY
+    let rows = client
+        .query(
+            "SELECT n.nspname::text, t.relname::text, c.relname::text,
+                    pg_relation_size(c.oid), pg_size_pretty(pg_relation_size(c.oid))
+             FROM pg_index i
+             JOIN pg_class c ON c.oid = i.indexrelid
+             JOIN pg_class t ON t.oid = i.indrelid
+             JOIN pg_namespace n ON n.oid = t.relnamespace
+             WHERE NOT i.indisvalid
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+                AND ($1::text IS NULL OR n.nspname = $1)
+             ORDER BY n.nspname, c.relname",
+            &[&schema],
+        )
+        .await
+        .map_err(map_err)?;
+    for row in &rows {
+        issues.push(issue(
+            "invalid",
+            row,
+            "Geçersiz indeks; sorgularda kullanılmıyor ama yazmaları yavaşlatıyor".to_string(),
+            format!("REINDEX INDEX CONCURRENTLY {};", qualified_index(row)),
+        ));
+    }
+
+    // Duplicate (same definition) and overlapping (key is a prefix of another btree key)
+    let rows = client
+        .query(
+            "SELECT n.nspname::text, t.relname::text, ca.relname::text,
+                    pg_relation_size(ca.oid), pg_size_pretty(pg_relation_size(ca.oid)),
+                    cb.relname::text,
+                    a.indkey::text = b.indkey::text as exact,
+                    acon.conname, afk.referenced
+             FROM pg_index a
+             JOIN pg_index b ON b.indrelid = a.indrelid AND b.indexrelid <> a.indexrelid
+             LEFT JOIN LATERAL (
+                SELECT con.conname::text FROM pg_constraint con
+                WHERE con.conindid = a.indexrelid AND con.conrelid = a.indrelid
+                    AND con.contype IN ('p', 'u', 'x')
+             ) acon ON true
+             LEFT JOIN LATERAL (
+                SELECT con.conname::text FROM pg_constraint con
+                WHERE con.conindid = b.indexrelid AND con.conrelid = b.indrelid
+                    AND con.contype IN ('p', 'u', 'x')
+             ) bcon ON true
+             CROSS JOIN LATERAL (
+                SELECT EXISTS (SELECT 1 FROM pg_constraint fk
+                               WHERE fk.conindid = a.indexrelid AND fk.contype = 'f') as referenced
+             ) afk
+             JOIN pg_class ca ON ca.oid = a.indexrelid
+             JOIN pg_class cb ON cb.oid = b.indexrelid
+             JOIN pg_class t ON t.oid = a.indrelid
+             JOIN pg_namespace n ON n.oid = t.relnamespace
+             WHERE ca.relam = cb.relam
+                AND NOT a.indisprimary
+                AND a.indisvalid AND b.indisvalid
+                AND COALESCE(pg_get_expr(a.indexprs, a.indrelid), '')
+                    = COALESCE(pg_get_expr(b.indexprs, b.indrelid), '')
+                AND COALESCE(pg_get_expr(a.indpred, a.indrelid), '')
+                    = COALESCE(pg_get_expr(b.indpred, b.indrelid), '')
+                AND (
+                    -- exact duplicate: keep primary, unique and constraint-backed
+                    -- ones first, otherwise the older index
+                    (a.indkey::text = b.indkey::text
+                     AND a.indclass::text = b.indclass::text
+                     AND (b.indisprimary, b.indisunique, bcon.conname IS NOT NULL, a.indexrelid)
+                         > (a.indisprimary, a.indisunique, acon.conname IS NOT NULL, b.indexrelid))
+                    OR
+                    -- overlapping: a's key is a leading prefix of b's btree key
+                    (NOT a.indisunique
+                     AND ca.relam = (SELECT oid FROM pg_am WHERE amname = 'btree')
+                     AND a.indnatts = a.indnkeyatts
+                     AND b.indkey::text LIKE a.indkey::text || ' %'
+                     AND b.indclass::text LIKE a.indclass::text || ' %')
+                )
+                AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+                AND ($1::text IS NULL OR n.nspname = $1)
+             ORDER BY n.nspname, t.relname, ca.relname",
+            &[&schema],
+        )
+        .await
+        .map_err(map_err)?;
+    let mut reported: Vec<String> = Vec::new();
+    for row in &rows {
+        let key = qualified_index(row);
+        if reported.contains(&key) {
+            continue;
+        }
+        let other: String = row.get(5);
+        let exact: bool = row.get(6);
+        let constraint_name: Option<String> = row.get(7);
+        let referenced: bool = row.get(8);
+        // Foreign keys depend on the index; it can't be dropped while they exist
+        if referenced {
+            continue;
+        }
+        let (kind, detail) = if exact {
+            ("duplicate", format!("{} ile aynı tanıma sahip", other))
+        } else {
+            ("overlapping", format!("{} indeksinin ön ekiyle aynı; gereksiz olabilir", other))
+        };
+        // An index that enforces a constraint goes away with the constraint
+        let suggestion = match constraint_name {
+            Some(name) => format!(
+                "ALTER TABLE {}.{} DROP CONSTRAINT {};",
+                quote_ident(&row.get::<_, String>(0)),
+                quote_ident(&row.get::<_, String>(1)),
+                quote_ident(&name)
+            ),
+            None => format!("DROP INDEX CONCURRENTLY {};", key),
+        };
+        issues.push(issue(kind, row, detail, suggestion));
+        reported.push(key);
+    }
+
+    // Bloat: compare btree size with an estimate from reltuples and column widths
+    let rows = client
+        .query(
+            "WITH est AS (
+                SELECT n.nspname::text as schema_name, t.relname::text as table_name,
+                       c.relname::text as index_name, c.oid,
+                       c.relpages::bigint as pages,
+                       current_setting('block_size')::bigint as bs,
+                       CEIL(c.reltuples * (12 + CEIL(COALESCE(w.width, 0) / 8.0) * 8)
+                            / ((current_setting('block_size')::bigint - 40)
+                               * COALESCE(substring(array_to_string(c.reloptions, ' ')
+                                          FROM 'fillfactor=([0-9]+)')::int, 90) / 100.0)) + 1
+                           as expected_pages
+                FROM pg_index i
+                JOIN pg_class c ON c.oid = i.indexrelid
+                JOIN pg_class t ON t.oid = i.indrelid
+                JOIN pg_namespace n ON n.oid = t.relnamespace
+                JOIN pg_am am ON am.oid = c.relam
+                LEFT JOIN LATERAL (
+                    SELECT SUM(s.avg_width) as width
+                    FROM pg_attribute a
+                    JOIN pg_stats s ON s.schemaname = n.nspname
+                        AND s.tablename = t.relname
+                        AND s.attname = a.attname
+                    WHERE a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+                ) w ON true
+                WHERE am.amname = 'btree'
+                    AND c.relpages > 10
+                    AND c.reltuples > 0
+                    AND NOT 0 = ANY(i.indkey::int2[])
+                    AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+                    AND ($1::text IS NULL OR n.nspname = $1)
+             )
+             SELECT schema_name, table_name, index_name,
+                    pg_relation_size(oid), pg_size_pretty(pg_relation_size(oid)),
+                    ((pages - expected_pages) * bs)::bigint as bloat_bytes,
+                    ((pages - expected_pages) / pages::float8)::float8 as bloat_ratio
+             FROM est
+             WHERE pages > expected_pages
+             ORDER BY bloat_bytes DESC",
+            &[&schema],
+        )
+        .await
+        .map_err(map_err)?;
+    for row in &rows {
+        let bloat_bytes: i64 = row.get(5);
+        let bloat_ratio: f64 = row.get(6);
+        if bloat_ratio < BLOAT_MIN_RATIO || bloat_bytes < BLOAT_MIN_BYTES {
+            continue;
+        }
+        issues.push(issue(
+            "bloat",
+            row,
+            format!(
+                "Tahmini şişkinlik %{:.0} (~{} MB)",
+                bloat_ratio * 100.0,
+                bloat_bytes / (1024 * 1024)
+            ),
+            format!("REINDEX INDEX CONCURRENTLY {};", qualified_index(row)),
+        ));
+    }
+
+    Ok(issues)
+}
@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tokio::sync::Mutex;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident};
+
+// ── VACUUM / ANALYZE / REINDEX / CLUSTER ────────────────────────────────
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MaintenanceOperation {
+    Vacuum {
+        #[serde(default)]
+        full: bool,
+        #[serde(default)]
+        freeze: bool,
+        #[serde(default)]
+        analyze: bool,
+    },
+    Analyze,
+    Reindex {
+        #[serde(default)]
+        concurrently: bool,
+    },
+    /// Without `index` the table's clustered index is used; in a schema only
+    /// tables that have one are processed.
+    Cluster {
+        #[serde(default)]
+        index: Option<String>,
+    },
+}
+
+/// Without `table` every table of the schema is processed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRequest {
+    pub schema: String,
+    #[serde(default)]
+    pub table: Option<String>,
+    pub operation: MaintenanceOperation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceProgress {
+    pub table: String,
+    /// 1-based position of `table` among the tables being processed
+    pub table_index: usize,
+    pub table_count: usize,
+    pub phase: Option<String>,
+    pub blocks_total: Option<i64>,
+    pub blocks_done: Option<i64>,
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceResult {
+    pub statements: Vec<String>,
+    pub tables_processed: usize,
+    pub cancelled: bool,
+    pub execution_time_ms: u128,
+}
+
+struct RunningOperation {
+    connection_id: String,
+    pid: i32,
+    cancelled: bool,
+}
+
+pub struct MaintenanceManager {
+    operations: HashMap<String, RunningOperation>,
+}
+
+impl MaintenanceManager {
+    pub fn new() -> Self {
+        Self {
+            operations: HashMap::new(),
+        }
+    }
+}
+
+pub type AppMaintenanceManager = Arc<Mutex<MaintenanceManager>>;
+
+pub fn create_maintenance_manager() -> AppMaintenanceManager {
+    Arc::new(Mutex::new(MaintenanceManager::new()))
+}
+
+/// A long-running command on its own session that `cancel_maintenance`
+/// can stop by `operation_id` (maintenance runs and index builds).
+pub struct CancellableOperation<'a> {
+    pub operations: &'a AppMaintenanceManager,
+    pub connection_id: &'a str,
+    pub operation_id: &'a str,
+}
+
+impl CancellableOperation<'_> {
+    pub(crate) async fn register(&self, pid: i32) {
+        self.operations.lock().await.operations.insert(
+            self.operation_id.to_string(),
+            RunningOperation {
+                connection_id: self.connection_id.to_string(),
+                pid,
+                cancelled: false,
+            },
+        );
+    }
+
+    pub(crate) async fn is_cancelled(&self) -> bool {
+        self.operations
+            .lock()
+            .await
+            .operations
+            .get(self.operation_id)
+            .is_some_and(|op| op.cancelled)
+    }
+
+    /// Unregisters the operation and returns whether it was cancelled.
+    pub(crate) async fn finish(&self) -> bool {
+        self.operations
+            .lock()
+            .await
+            .operations
+            .remove(self.operation_id)
+            .is_some_and(|op| op.cancelled)
+    }
+}
+
+fn build_statement(operation: &MaintenanceOperation, qualified: &str) -> String {
+    match operation {
+        MaintenanceOperation::Vacuum {
+            full,
+            freeze,
+            analyze,
+        } => {
+            let mut options = Vec::new();
+            if *full {
+                options.push("FULL");
+            }
+            if *freeze {
+                options.push("FREEZE");
+            }
+            if *analyze {
+                options.push("ANALYZE");
+            }
+            if options.is_empty() {
+                format!("VACUUM {}", qualified)
+            } else {
+                format!("VACUUM ({}) {}", options.join(", "), qualified)
+            }
+        }
+        MaintenanceOperation::Analyze => format!("ANALYZE {}", qualified),
+        MaintenanceOperation::Reindex { concurrently } => format!(
+            "REINDEX TABLE {}{}",
+            if *concurrently { "CONCURRENTLY " } else { "" },
+            qualified
+        ),
+        MaintenanceOperation::Cluster { index } => match index {
+            Some(index) if !index.trim().is_empty() => {
+                format!("CLUSTER {} USING {}", qualified, quote_ident(index))
+            }
+            _ => format!("CLUSTER {}", qualified),
+        },
+    }
+}
+
+async fn target_tables(
+    client: &Arc<Client>,
+    request: &MaintenanceRequest,
+) -> Result<Vec<String>, String> {
+    if let Some(table) = request.table.as_deref().filter(|t| !t.is_empty()) {
+        return Ok(vec![table.to_string()]);
+    }
+
+    let clustered_only = matches!(request.operation, MaintenanceOperation::Cluster { .. });
+    let rows = client
+        .query(
+            "SELECT c.relname::text
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1
+                AND c.relkind IN ('r', 'm')
+                AND (NOT $2 OR EXISTS (
+                    SELECT 1 FROM pg_index i WHERE i.indrelid = c.oid AND i.indisclustered
+                ))
+             ORDER BY c.relname",
+            &[&request.schema, &clustered_only],
+        )
+        .await
+        .map_err(|e| format!("Tablo listesi alınamadı: {}", e))?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Reads whichever progress view the backend currently reports in. VACUUM FULL
+/// and CLUSTER report in `pg_stat_progress_cluster`, REINDEX in
+/// `pg_stat_progress_create_index`.
+async fn read_progress(client: &Arc<Client>, pid: i32) -> Option<(String, i64, i64)> {
+    let queries = [
+        "SELECT phase, heap_blks_total, heap_blks_scanned FROM pg_stat_progress_vacuum WHERE pid = $1",
+        "SELECT phase, sample_blks_total, sample_blks_scanned FROM pg_stat_progress_analyze WHERE pid = $1",
+        "SELECT phase, heap_blks_total, heap_blks_scanned FROM pg_stat_progress_cluster WHERE pid = $1",
+        "SELECT phase, blocks_total, blocks_done FROM pg_stat_progress_create_index WHERE pid = $1",
+    ];
+    for sql in queries {
+        // Older servers lack some of the views; skip those
+        if let Ok(Some(row)) = client.query_opt(sql, &[&pid]).await {
+            return Some((row.get(0), row.get(1), row.get(2)));
+        }
+    }
+    None
+}
+
+/// Runs the operation table by table on its own session, polling progress on
+/// the main connection. `cancel_maintenance` with the same `operation_id`
+/// cancels the running statement and skips the remaining tables.
+pub async fn run_maintenance(
+    client: &Arc<Client>,
+    session: Client,
+    operation: CancellableOperation<'_>,
+    request: &MaintenanceRequest,
+    channel: Channel<MaintenanceProgress>,
+) -> Result<MaintenanceResult, String> {
+    let start = Instant::now();
+    let tables = target_tables(client, request).await?;
+    if tables.is_empty() {
+        return Err("İşlem uygulanacak tablo bulunamadı".to_string());
+    }
+
+    let pid: i32 = session
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    operation.register(pid).await;
+
+    let mut statements = Vec::new();
+    let mut outcome = Ok(());
+    for (i, table_name) in tables.iter().enumerate() {
+        let qualified = format!("{}.{}", quote_ident(&request.schema), quote_ident(table_name));
+        let sql = build_statement(&request.operation, &qualified);
+
+        let result = {
+            let run = session.batch_execute(&sql);
+            tokio::pin!(run);
+            let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    res = &mut run => break res,
+                    _ = ticker.tick() => {
+                        let progress = read_progress(client, pid).await;
+                        let (phase, blocks_total, blocks_done) = match progress {
+                            Some((phase, total, done)) => (Some(phase), Some(total), Some(done)),
+                            None => (None, None, None),
+                        };
+                        let percent = match (blocks_total, blocks_done) {
+                            (Some(total), Some(done)) if total > 0 => {
+                                Some(done as f64 * 100.0 / total as f64)
+                            }
+                            _ => None,
+                        };
+                        channel
+                            .send(MaintenanceProgress {
+                                table: table_name.clone(),
+                                table_index: i + 1,
+                                table_count: tables.len(),
+                                phase,
+                                blocks_total,
+                                blocks_done,
+                                percent,
+                            })
+                            .ok();
+                    }
+                }
+            }
+        };
+
+        statements.push(sql);
+        if let Err(e) = result {
+            outcome = Err(format_db_error(&e));
+            break;
+        }
+        if operation.is_cancelled().await {
+            break;
+        }
+    }
+
+    let cancelled = operation.finish().await;
+    if let Err(e) = outcome {
+        if !cancelled {
+            return Err(e);
+        }
+        // The cancelled statement did not complete
+        statements.pop();
+    }
+
+    Ok(MaintenanceResult {
+        tables_processed: statements.len(),
+        statements,
+        cancelled,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+}
+
+/// Returns the connection whose session runs the operation, or None if it
+/// has already finished.
+pub async fn mark_cancelled(
+    operations: &AppMaintenanceManager,
+    operation_id: &str,
+) -> Option<(String, i32)> {
+    let mut manager = operations.lock().await;
+    let op = manager.operations.get_mut(operation_id)?;
+    op.cancelled = true;
+    Some((op.connection_id.clone(), op.pid))
+}
+
+pub async fn cancel_maintenance(client: &Arc<Client>, pid: i32) -> Result<bool, String> {
+    let row = client
+        .query_one("SELECT pg_cancel_backend($1)", &[&pid])
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(row.get(0))
+}
+
+// ── Maintenance stats ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableMaintenanceStats {
+    pub schema: String,
+    pub table_name: String,
+    pub live_tuples: i64,
+    pub dead_tuples: i64,
+    pub dead_ratio: f64,
+    pub modifications_since_analyze: i64,
+    pub last_vacuum: Option<String>,
+    pub last_autovacuum: Option<String>,
+    pub last_analyze: Option<String>,
+    pub last_autoanalyze: Option<String>,
+    pub vacuum_count: i64,
+    pub autovacuum_count: i64,
+    pub analyze_count: i64,
+    pub autoanalyze_count: i64,
+    pub size_bytes: i64,
+    pub size: String,
+    /// Estimated from reltuples and average row width; None without statistics
+    pub estimated_bloat_bytes: Option<i64>,
+    pub estimated_bloat_ratio: Option<f64>,
+}
+
+pub async fn get_maintenance_stats(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<TableMaintenanceStats>, String> {
+    let rows = client
+        .query(
+            "WITH est AS (
+                SELECT s.relid, c.relpages::bigint as pages,
+                       current_setting('block_size')::bigint as bs,
+                       CASE WHEN c.reltuples > 0 AND w.width IS NOT NULL THEN
+                           CEIL(c.reltuples * (28 + CEIL(w.width / 8.0) * 8)
+                                / ((current_setting('block_size')::bigint - 24)
+                                   * COALESCE(substring(array_to_string(c.reloptions, ' ')
+                                              FROM 'fillfactor=([0-9]+)')::int, 100) / 100.0))
+                       END as expected_pages
+                FROM pg_stat_user_tables s
+                JOIN pg_class c ON c.oid = s.relid
+                LEFT JOIN LATERAL (
+                    SELECT SUM(st.avg_width) as width
+                    FROM pg_stats st
+                    WHERE st.schemaname = s.schemaname AND st.tablename = s.relname
+                ) w ON true
+                WHERE s.schemaname = $1
+             )
+             SELECT s.schemaname::text, s.relname::text, s.n_live_tup, s.n_dead_tup,
+                    CASE WHEN s.n_live_tup + s.n_dead_tup > 0
+                         THEN s.n_dead_tup::float8 / (s.n_live_tup + s.n_dead_tup)
+                         ELSE 0 END,
+                    s.n_mod_since_analyze,
+                    s.last_vacuum::text, s.last_autovacuum::text,
+                    s.last_analyze::text, s.last_autoanalyze::text,
+                    s.vacuum_count, s.autovacuum_count, s.analyze_count, s.autoanalyze_count,
+                    pg_table_size(s.relid), pg_size_pretty(pg_table_size(s.relid)),
+                    CASE WHEN e.expected_pages IS NOT NULL
+                         THEN (GREATEST(e.pages - e.expected_pages, 0) * e.bs)::bigint END,
+                    CASE WHEN e.expected_pages IS NOT NULL AND e.pages > 0
+                         THEN (GREATEST(e.pages - e.expected_pages, 0) / e.pages::float8)::float8 END
+             FROM pg_stat_user_tables s
+             JOIN est e ON e.relid = s.relid
+             ORDER BY s.n_dead_tup DESC, s.relname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Bakım istatistikleri alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TableMaintenanceStats {
+            schema: row.get(0),
+            table_name: row.get(1),
+            live_tuples: row.get(2),
+            dead_tuples: row.get(3),
+            dead_ratio: row.get(4),
+            modifications_since_analyze: row.get(5),
+            last_vacuum: row.get(6),
+            last_autovacuum: row.get(7),
+            last_analyze: row.get(8),
+            last_autoanalyze: row.get(9),
+            vacuum_count: row.get(10),
+            autovacuum_count: row.get(11),
+            analyze_count: row.get(12),
+            autoanalyze_count: row.get(13),
+            size_bytes: row.get(14),
+            size: row.get(15),
+            estimated_bloat_bytes: row.get(16),
+            estimated_bloat_ratio: row.get(17),
+        })
+        .collect())
+}
@@ -1,3 +1,22 @@
+pub mod activity;
+pub mod alter;
+pub mod changes;
 pub mod connection;
+pub mod cursor;
+pub mod data_diff;
+pub mod databases;
+pub mod ddl;
+pub mod filter;
+pub mod functions;
+pub mod indexes;
+pub mod maintenance;
 pub mod models;
+pub mod params;
 pub mod queries;
+pub mod references;
+pub mod roles;
+pub mod schema_diff;
+pub mod settings;
+pub mod stat_statements;
+pub mod table_ops;
+pub mod value;
@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::Client;
+
+use super::queries::format_db_error;
+
+// ── Query parameters ($1 / :name placeholders) ──────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParam {
+    pub value: Option<String>,
+    /// Explicit SQL type (e.g. "integer", "text[]"); inferred by the server when omitted
+    pub data_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParamInfo {
+    pub position: usize,
+    pub name: String,
+    pub data_type: String,
+}
+
+/// A query whose placeholders are rewritten to `$n::text::<type>` so every
+/// value can be sent as text and converted by the server.
+pub struct BoundQuery {
+    pub sql: String,
+    pub values: Vec<Option<String>>,
+}
+
+impl BoundQuery {
+    pub fn param_types(&self) -> Vec<Type> {
+        vec![Type::TEXT; self.values.len()]
+    }
+
+    pub fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.values
+            .iter()
+            .map(|v| v as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+enum Segment {
+    Text(String),
+    Param(usize),
+}
+
+struct ParsedQuery {
+    segments: Vec<Segment>,
+    names: Vec<String>,
+}
+
+impl ParsedQuery {
+    fn render<F: Fn(usize) -> String>(&self, placeholder: F) -> String {
+        self.segments
+            .iter()
+            .map(|s| match s {
+                Segment::Text(t) => t.clone(),
+                Segment::Param(n) => placeholder(*n),
+            })
+            .collect()
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Splits the SQL into text and placeholder segments, skipping string
+/// literals, quoted identifiers, comments, dollar-quoted bodies, `::` casts
+/// and array slices such as `arr[1:n]`.
+fn parse_placeholders(sql: &str) -> Result<ParsedQuery, String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut has_positional = false;
+    let mut has_named = false;
+    let mut bracket_depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' => {
+                let escapes = i > 0
+                    && (chars[i - 1] == 'E' || chars[i - 1] == 'e')
+                    && (i < 2 || !is_ident_char(chars[i - 2]));
+                text.push(c);
+                i += 1;
+                while i < chars.len() {
+                    text.push(chars[i]);
+                    if escapes && chars[i] == '\\' && i + 1 < chars.len() {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == '\'' {
+                        if i + 1 < chars.len() && chars[i + 1] == '\'' {
+                            text.push('\'');
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            '"' => {
+                text.push(c);
+                i += 1;
+                while i < chars.len() {
+                    text.push(chars[i]);
+                    i += 1;
+                    if chars[i - 1] == '"' {
+                        break;
+                    }
+                }
+            }
+            '-' if i + 1 < chars.len() && chars[i + 1] == '-' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '/' if i + 1 < chars.len() && chars[i + 1] == '*' => {
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && i + 1 < chars.len() && chars[i + 1] == '*' {
+                        depth += 1;
+                        text.push_str("/*");
+                        i += 2;
+                    } else if chars[i] == '*' && i + 1 < chars.len() && chars[i + 1] == '/' {
+                        depth -= 1;
+                        text.push_str("*/");
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        text.push(chars[i]);
+                        i += 1;
+                    }
+                }
+            }
+            '$' if i + 1 < chars.len()
+                && chars[i + 1].is_ascii_digit()
+                && (i == 0 || !is_ident_char(chars[i - 1])) =>
+            {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && chars[end].is_ascii_digit() {
+                    end += 1;
+                }
+                let num: String = chars[start..end].iter().collect();
+                let position: usize = num
+                    .parse()
+                    .map_err(|_| format!("Geçersiz parametre: ${}", num))?;
+                if position == 0 {
+                    return Err("Parametre numaraları $1'den başlamalı".to_string());
+                }
+                has_positional = true;
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+                segments.push(Segment::Param(position));
+                i = end;
+            }
+            '$' if i == 0 || !is_ident_char(chars[i - 1]) => {
+                // Dollar-quoted string: $tag$ ... $tag$
+                let mut end = i + 1;
+                while end < chars.len() && is_ident_char(chars[end]) {
+                    end += 1;
+                }
+                let tag_ok = end < chars.len()
+                    && chars[end] == '$'
+                    && (end == i + 1 || is_ident_start(chars[i + 1]));
+                if !tag_ok {
+                    text.push(c);
+                    i += 1;
+                    continue;
+                }
+                let tag: String = chars[i..=end].iter().collect();
+                let tag_chars: Vec<char> = tag.chars().collect();
+                text.push_str(&tag);
+                i = end + 1;
+                while i < chars.len() {
+                    if chars[i..].starts_with(&tag_chars) {
+                        text.push_str(&tag);
+                        i += tag_chars.len();
+                        break;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+            }
+            ':' if i + 1 < chars.len() && chars[i + 1] == ':' => {
+                text.push_str("::");
+                i += 2;
+            }
+            '[' | ']' => {
+                if c == '[' {
+                    bracket_depth += 1;
+                } else {
+                    bracket_depth = bracket_depth.saturating_sub(1);
+                }
+                text.push(c);
+                i += 1;
+            }
+            ':' if bracket_depth == 0 && i + 1 < chars.len() && is_ident_start(chars[i + 1]) => {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && is_ident_char(chars[end]) {
+                    end += 1;
+                }
+                let name: String = chars[start..end].iter().collect();
+                let position = match names.iter().position(|n| *n == name) {
+                    Some(idx) => idx + 1,
+                    None => {
+                        names.push(name);
+                        names.len()
+                    }
+                };
+                has_named = true;
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+                segments.push(Segment::Param(position));
+                i = end;
+            }
+            _ => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    if has_positional && has_named {
+        return Err("Konumsal ($1) ve isimli (:ad) parametreler birlikte kullanılamaz".to_string());
+    }
+
+    segments.push(Segment::Text(text));
+    Ok(ParsedQuery { segments, names })
+}
+
+/// Renders a server-reported type so it can be used in a cast.
+pub(crate) fn type_cast_name(ty: &Type) -> String {
+    if *ty == Type::UNKNOWN {
+        "text".to_string()
+    } else if ty.schema() == "pg_catalog" {
+        ty.name().to_string()
+    } else {
+        format!(
+            "\"{}\".\"{}\"",
+            ty.schema().replace('"', "\"\""),
+            ty.name().replace('"', "\"\"")
+        )
+    }
+}
+
+/// Resolves a user-supplied type name through `regtype`, so only names of
+/// existing types ever reach the rewritten SQL. The canonical name comes
+/// back quoted and schema-qualified where needed.
+async fn resolve_type_name(client: &Arc<Client>, name: &str) -> Result<String, String> {
+    let row = client
+        .query_one("SELECT $1::text::regtype::text", &[&name.trim()])
+        .await
+        .map_err(|e| {
+            format!(
+                "Geçersiz parametre tipi '{}': {}",
+                name,
+                format_db_error(&e)
+            )
+        })?;
+    Ok(row.get(0))
+}
+
+/// Resolves every explicit parameter type, keeping `None` for the ones the
+/// server should infer.
+async fn resolve_param_types(
+    client: &Arc<Client>,
+    params: &[QueryParam],
+) -> Result<Vec<Option<String>>, String> {
+    let mut resolved = Vec::with_capacity(params.len());
+    for p in params {
+        resolved.push(match p.data_type.as_deref() {
+            Some(t) if !t.trim().is_empty() => Some(resolve_type_name(client, t).await?),
+            _ => None,
+        });
+    }
+    Ok(resolved)
+}
+
+/// Prepares the query once to let the server infer every parameter type.
+/// Explicitly typed params are cast up front so they steer the inference.
+async fn infer_param_types(
+    client: &Arc<Client>,
+    parsed: &ParsedQuery,
+    explicit: &[Option<String>],
+) -> Result<Vec<Type>, String> {
+    let sql = parsed.render(|n| match explicit.get(n - 1).and_then(|t| t.as_ref()) {
+        Some(typ) => format!("${}::{}", n, typ),
+        None => format!("${}", n),
+    });
+
+    let stmt = client
+        .prepare(sql.trim())
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    Ok(stmt.params().to_vec())
+}
+
+fn param_name(parsed: &ParsedQuery, position: usize) -> String {
+    parsed
+        .names
+        .get(position - 1)
+        .cloned()
+        .unwrap_or_else(|| format!("${}", position))
+}
+
+pub async fn describe_query_params(
+    client: &Arc<Client>,
+    sql: &str,
+) -> Result<Vec<QueryParamInfo>, String> {
+    let parsed = parse_placeholders(sql.trim())?;
+    let types = infer_param_types(client, &parsed, &[]).await?;
+
+    Ok(types
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| QueryParamInfo {
+            position: idx + 1,
+            name: param_name(&parsed, idx + 1),
+            data_type: type_cast_name(ty),
+        })
+        .collect())
+}
+
+pub async fn bind_query(
+    client: &Arc<Client>,
+    sql: &str,
+    params: &[QueryParam],
+) -> Result<BoundQuery, String> {
+    let parsed = parse_placeholders(sql.trim())?;
+    let explicit = resolve_param_types(client, params).await?;
+    let types = infer_param_types(client, &parsed, &explicit).await?;
+
+    if params.len() != types.len() {
+        return Err(format!(
+            "Sorgu {} parametre bekliyor, {} değer verildi",
+            types.len(),
+            params.len()
+        ));
+    }
+
+    let casts: Vec<String> = explicit
+        .into_iter()
+        .zip(types.iter())
+        .map(|(t, ty)| t.unwrap_or_else(|| type_cast_name(ty)))
+        .collect();
+
+    let bound_sql = parsed.render(|n| format!("(${}::text::{})", n, casts[n - 1]));
+
+    Ok(BoundQuery {
+        sql: bound_sql.trim().to_string(),
+        values: params.iter().map(|p| p.value.clone()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(sql: &str) -> (String, Vec<String>) {
+        let parsed = parse_placeholders(sql).unwrap();
+        (parsed.render(|n| format!("${}", n)), parsed.names)
+    }
+
+    #[test]
+    fn positional_placeholders_are_kept() {
+        let (sql, names) = numbered("SELECT * FROM t WHERE a = $1 AND b = $2");
+        assert_eq!(sql, "SELECT * FROM t WHERE a = $1 AND b = $2");
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn named_placeholders_are_numbered_in_order_and_reused() {
+        let (sql, names) = numbered("SELECT :b, :a, :b");
+        assert_eq!(sql, "SELECT $1, $2, $1");
+        assert_eq!(names, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn casts_are_not_placeholders() {
+        let (sql, names) = numbered("SELECT x::int, :v::text");
+        assert_eq!(sql, "SELECT x::int, $1::text");
+        assert_eq!(names, vec!["v"]);
+    }
+
+    #[test]
+    fn literals_comments_and_dollar_bodies_are_skipped() {
+        let input =
+            "SELECT ':a', E'\\':b', \":c\", $$ :d $1 $$, $f$:e$f$ -- :g\n/* :h /* :i */ */ FROM t";
+        let (sql, names) = numbered(input);
+        assert_eq!(sql, input);
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn array_slices_are_not_placeholders() {
+        let (sql, names) = numbered("SELECT arr[1:n], arr[lo:hi][2:3] FROM t WHERE id = :id");
+        assert_eq!(sql, "SELECT arr[1:n], arr[lo:hi][2:3] FROM t WHERE id = $1");
+        assert_eq!(names, vec!["id"]);
+    }
+
+    #[test]
+    fn positional_inside_brackets_still_counts() {
+        let (sql, _) = numbered("SELECT arr[$1:$2] FROM t");
+        assert_eq!(sql, "SELECT arr[$1:$2] FROM t");
+    }
+
+    #[test]
+    fn mixing_styles_is_rejected() {
+        assert!(parse_placeholders("SELECT $1, :a").is_err());
+    }
+
+    #[test]
+    fn zero_position_is_rejected() {
+        assert!(parse_placeholders("SELECT $0").is_err());
+    }
+}
@@ -1,9 +1,14 @@
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio_postgres::Client;
-use tokio_postgres::types::Type;
+
+use super::changes::{self, ReturnedRows, RowChange, RowExpectation, RowKey, UpdateRowResult};
+use super::filter::{self, FilterGroup, KeyColumn, SortDirection, SortSpec, SqlParams};
+use super::params::{bind_query, type_cast_name, QueryParam};
+use super::value::{self, PgValue};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaInfo {
@@ -36,6 +41,14 @@ pub struct IndexInfo {
     pub is_primary: bool,
     pub columns: String,
     pub index_type: String,
+    pub include_columns: Vec<String>,
+    /// WHERE clause of a partial index
+    pub predicate: Option<String>,
+    pub definition: String,
+    pub is_valid: bool,
+    pub size_bytes: i64,
+    pub size: String,
+    pub scans: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,12 +66,172 @@ pub struct FunctionInfo {
     pub schema: String,
     pub return_type: String,
     pub argument_types: String,
+    pub kind: String, // "function", "procedure", "aggregate" or "window"
 }
 
+/// Sequence values are bigints and are sent as strings, since they don't
+/// fit a JavaScript number (`max_value` defaults to 2^63 - 1).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SequenceInfo {
     pub name: String,
     pub schema: String,
+    pub data_type: String,
+    pub start_value: String,
+    pub min_value: String,
+    pub max_value: String,
+    pub increment_by: String,
+    pub cycle: bool,
+    pub cache_size: String,
+    pub last_value: Option<String>,
+    pub owner: String,
+    /// "table.column" for sequences owned by a serial/identity column
+    pub owned_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterializedViewInfo {
+    pub name: String,
+    pub schema: String,
+    pub owner: String,
+    pub is_populated: bool,
+    pub size: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignTableInfo {
+    pub name: String,
+    pub schema: String,
+    pub server: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionedTableInfo {
+    pub name: String,
+    pub schema: String,
+    pub partition_key: String,
+    pub partition_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeInfo {
+    pub name: String,
+    pub schema: String,
+    pub kind: String, // "composite", "domain", "enum" or "range"
+    /// Domain base type or range subtype
+    pub base_type: Option<String>,
+    pub enum_values: Vec<String>,
+    /// Composite attributes as "name type"
+    pub attributes: Vec<String>,
+    /// Domain NOT NULL / CHECK constraints
+    pub constraints: Vec<String>,
+    pub default_value: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    pub name: String,
+    pub schema: String,
+    pub table_name: String,
+    pub timing: String, // "BEFORE", "AFTER" or "INSTEAD OF"
+    pub events: Vec<String>,
+    pub level: String, // "ROW" or "STATEMENT"
+    pub function: String,
+    pub enabled: bool,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTriggerInfo {
+    pub name: String,
+    pub event: String,
+    pub function: String,
+    pub enabled: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyInfo {
+    pub name: String,
+    pub schema: String,
+    pub table_name: String,
+    pub permissive: bool,
+    pub command: String,
+    pub roles: Vec<String>,
+    pub using_expr: Option<String>,
+    pub with_check: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleInfo {
+    pub name: String,
+    pub schema: String,
+    pub table_name: String,
+    pub event: String,
+    pub is_instead: bool,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub version: String,
+    pub schema: String,
+    pub default_version: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleInfo {
+    pub name: String,
+    pub is_superuser: bool,
+    pub can_login: bool,
+    pub can_create_db: bool,
+    pub can_create_role: bool,
+    pub inherit: bool,
+    pub replication: bool,
+    pub bypass_rls: bool,
+    pub connection_limit: i32,
+    pub valid_until: Option<String>,
+    /// Roles this role is a member of
+    pub member_of: Vec<String>,
+    /// Roles that are members of this role
+    pub members: Vec<String>,
+    /// Predefined "pg_*" roles
+    pub is_system: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivilegeGrant {
+    pub grantee: String, // role name or "PUBLIC"
+    pub privileges: Vec<String>,
+    /// Privileges the grantee may grant on
+    pub grantable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectPrivileges {
+    /// "schema", "table", "view", "materialized_view", "foreign_table", "sequence",
+    /// "function" or "procedure"
+    pub object_type: String,
+    pub schema: String,
+    /// Functions include their identity arguments, e.g. "add(a integer, b integer)"
+    pub name: String,
+    pub owner: String,
+    pub grants: Vec<PrivilegeGrant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultPrivilegeInfo {
+    /// Role whose new objects get these privileges
+    pub role: String,
+    /// None when the default applies in every schema
+    pub schema: Option<String>,
+    pub object_type: String, // "table", "sequence", "function", "type" or "schema"
+    pub grants: Vec<PrivilegeGrant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,6 +288,204 @@ pub async fn list_schemas(client: &Arc<Client>) -> Result<Vec<SchemaInfo>, Strin
         .collect())
 }
 
+pub async fn list_roles(client: &Arc<Client>) -> Result<Vec<RoleInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT r.rolname, r.rolsuper, r.rolcanlogin, r.rolcreatedb, r.rolcreaterole,
+                    r.rolinherit, r.rolreplication, r.rolbypassrls, r.rolconnlimit,
+                    r.rolvaliduntil::text,
+                    ARRAY(SELECT g.rolname::text FROM pg_auth_members m
+                          JOIN pg_roles g ON g.oid = m.roleid
+                          WHERE m.member = r.oid ORDER BY g.rolname) as member_of,
+                    ARRAY(SELECT u.rolname::text FROM pg_auth_members m
+                          JOIN pg_roles u ON u.oid = m.member
+                          WHERE m.roleid = r.oid ORDER BY u.rolname) as members,
+                    r.rolname LIKE 'pg\\_%' as is_system,
+                    shobj_description(r.oid, 'pg_authid')
+             FROM pg_roles r
+             ORDER BY r.rolname LIKE 'pg\\_%', r.rolname",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Rol listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RoleInfo {
+            name: row.get(0),
+            is_superuser: row.get(1),
+            can_login: row.get(2),
+            can_create_db: row.get(3),
+            can_create_role: row.get(4),
+            inherit: row.get(5),
+            replication: row.get(6),
+            bypass_rls: row.get(7),
+            connection_limit: row.get(8),
+            valid_until: row.get(9),
+            member_of: row.get(10),
+            members: row.get(11),
+            is_system: row.get(12),
+            comment: row.get(13),
+        })
+        .collect())
+}
+
+/// (grantee, privilege, grantable) as returned by `aclexplode`.
+type AclEntry = (String, String, bool);
+
+/// Groups ACL rows into one entry per grantee.
+fn group_grants(entries: &[AclEntry]) -> Vec<PrivilegeGrant> {
+    let mut grants: Vec<PrivilegeGrant> = Vec::new();
+    for (grantee, privilege, grantable) in entries {
+        let idx = match grants.iter().position(|g| g.grantee == *grantee) {
+            Some(idx) => idx,
+            None => {
+                grants.push(PrivilegeGrant {
+                    grantee: grantee.clone(),
+                    privileges: Vec::new(),
+                    grantable: Vec::new(),
+                });
+                grants.len() - 1
+            }
+        };
+        grants[idx].privileges.push(privilege.clone());
+        if *grantable {
+            grants[idx].grantable.push(privilege.clone());
+        }
+    }
+    grants
+}
+
+/// Privileges on a schema and the objects in it, from `aclexplode`. Objects
+/// without an ACL get the built-in defaults (`acldefault`).
+pub async fn list_object_privileges(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<ObjectPrivileges>, String> {
+    let rows = client
+        .query(
+            "WITH objects AS (
+                SELECT 'schema' as object_type, n.nspname as schema_name, n.nspname::text as name,
+                       n.nspowner as owner, COALESCE(n.nspacl, acldefault('n', n.nspowner)) as acl,
+                       0 as sort
+                FROM pg_namespace n
+                WHERE n.nspname = $1
+                UNION ALL
+                SELECT CASE c.relkind
+                           WHEN 'v' THEN 'view'
+                           WHEN 'm' THEN 'materialized_view'
+                           WHEN 'f' THEN 'foreign_table'
+                           WHEN 'S' THEN 'sequence'
+                           ELSE 'table'
+                       END,
+                       n.nspname, c.relname::text, c.relowner,
+                       COALESCE(c.relacl, acldefault(CASE WHEN c.relkind = 'S' THEN 's' ELSE 'r' END::\"char\", c.relowner)),
+                       1
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm', 'f', 'S')
+                UNION ALL
+                SELECT CASE WHEN p.prokind = 'p' THEN 'procedure' ELSE 'function' END,
+                       n.nspname, p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                       p.proowner, COALESCE(p.proacl, acldefault('f', p.proowner)),
+                       2
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+                WHERE n.nspname = $1
+             )
+             SELECT o.object_type, o.schema_name::text, o.name, pg_get_userbyid(o.owner)::text,
+                    CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END,
+                    a.privilege_type, a.is_grantable
+             FROM objects o, aclexplode(o.acl) a
+             ORDER BY o.sort, o.object_type, o.name, a.grantee, a.privilege_type",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Yetki listesi alınamadı: {}", e))?;
+
+    let mut objects: Vec<(ObjectPrivileges, Vec<AclEntry>)> = Vec::new();
+    for row in &rows {
+        let object_type: String = row.get(0);
+        let name: String = row.get(2);
+        let entry = (row.get(4), row.get(5), row.get(6));
+        match objects.last_mut() {
+            Some((obj, entries)) if obj.object_type == object_type && obj.name == name => {
+                entries.push(entry);
+            }
+            _ => objects.push((
+                ObjectPrivileges {
+                    object_type,
+                    schema: row.get(1),
+                    name,
+                    owner: row.get(3),
+                    grants: Vec::new(),
+                },
+                vec![entry],
+            )),
+        }
+    }
+
+    Ok(objects
+        .into_iter()
+        .map(|(mut obj, entries)| {
+            obj.grants = group_grants(&entries);
+            obj
+        })
+        .collect())
+}
+
+pub async fn list_default_privileges(
+    client: &Arc<Client>,
+) -> Result<Vec<DefaultPrivilegeInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT d.oid, pg_get_userbyid(d.defaclrole)::text, n.nspname::text,
+                    CASE d.defaclobjtype
+                        WHEN 'r' THEN 'table'
+                        WHEN 'S' THEN 'sequence'
+                        WHEN 'f' THEN 'function'
+                        WHEN 'T' THEN 'type'
+                        ELSE 'schema'
+                    END,
+                    CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END,
+                    a.privilege_type, a.is_grantable
+             FROM pg_default_acl d
+             LEFT JOIN pg_namespace n ON n.oid = d.defaclnamespace
+             CROSS JOIN aclexplode(d.defaclacl) a
+             ORDER BY d.oid, a.grantee, a.privilege_type",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Varsayılan yetkiler alınamadı: {}", e))?;
+
+    let mut defaults: Vec<(u32, DefaultPrivilegeInfo, Vec<AclEntry>)> = Vec::new();
+    for row in &rows {
+        let oid: u32 = row.get(0);
+        let entry = (row.get(4), row.get(5), row.get(6));
+        match defaults.last_mut() {
+            Some((last, _, entries)) if *last == oid => entries.push(entry),
+            _ => defaults.push((
+                oid,
+                DefaultPrivilegeInfo {
+                    role: row.get(1),
+                    schema: row.get(2),
+                    object_type: row.get(3),
+                    grants: Vec::new(),
+                },
+                vec![entry],
+            )),
+        }
+    }
+
+    Ok(defaults
+        .into_iter()
+        .map(|(_, mut info, entries)| {
+            info.grants = group_grants(&entries);
+            info
+        })
+        .collect())
+}
+
 pub async fn list_tables(client: &Arc<Client>, schema: &str) -> Result<Vec<TableInfo>, String> {
     let rows = client
         .query(
@@ -234,16 +605,25 @@ pub async fn list_indexes(
                 i.relname as index_name,
                 ix.indisunique as is_unique,
                 ix.indisprimary as is_primary,
-                string_agg(a.attname, ', ' ORDER BY array_position(ix.indkey, a.attnum)) as columns,
-                am.amname as index_type
+                COALESCE((SELECT string_agg(pg_get_indexdef(ix.indexrelid, k, true), ', ' ORDER BY k)
+                          FROM generate_series(1, ix.indnkeyatts) k), '') as columns,
+                am.amname as index_type,
+                ARRAY(SELECT pg_get_indexdef(ix.indexrelid, k, true)
+                      FROM generate_series(ix.indnkeyatts + 1, ix.indnatts) k
+                      ORDER BY k) as include_columns,
+                pg_get_expr(ix.indpred, ix.indrelid) as predicate,
+                pg_get_indexdef(ix.indexrelid) as definition,
+                ix.indisvalid as is_valid,
+                pg_relation_size(ix.indexrelid) as size_bytes,
+                pg_size_pretty(pg_relation_size(ix.indexrelid)) as size,
+                COALESCE(s.idx_scan, 0) as scans
              FROM pg_index ix
              JOIN pg_class t ON t.oid = ix.indrelid
              JOIN pg_class i ON i.oid = ix.indexrelid
              JOIN pg_namespace n ON n.oid = t.relnamespace
              JOIN pg_am am ON am.oid = i.relam
-             JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
+             LEFT JOIN pg_stat_all_indexes s ON s.indexrelid = ix.indexrelid
              WHERE n.nspname = $1 AND t.relname = $2
-             GROUP BY i.relname, ix.indisunique, ix.indisprimary, am.amname
              ORDER BY i.relname",
             &[&schema, &table],
         )
@@ -258,6 +638,13 @@ pub async fn list_indexes(
             is_primary: row.get(2),
             columns: row.get(3),
             index_type: row.get(4),
+            include_columns: row.get(5),
+            predicate: row.get(6),
+            definition: row.get(7),
+            is_valid: row.get(8),
+            size_bytes: row.get(9),
+            size: row.get(10),
+            scans: row.get(11),
         })
         .collect())
 }
@@ -312,122 +699,783 @@ pub async fn list_functions(
             "SELECT
                 p.proname as name,
                 n.nspname as schema,
-                pg_get_function_result(p.oid) as return_type,
-                pg_get_function_identity_arguments(p.oid) as argument_types
+                COALESCE(pg_get_function_result(p.oid), '') as return_type,
+                pg_get_function_identity_arguments(p.oid) as argument_types,
+                CASE p.prokind
+                    WHEN 'p' THEN 'procedure'
+                    WHEN 'a' THEN 'aggregate'
+                    WHEN 'w' THEN 'window'
+                    ELSE 'function'
+                END as kind
              FROM pg_proc p
              JOIN pg_namespace n ON n.oid = p.pronamespace
              WHERE n.nspname = $1
-                AND p.prokind = 'f'
-             ORDER BY p.proname",
+             ORDER BY p.proname, argument_types",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Fonksiyon listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| FunctionInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            return_type: row.get(2),
+            argument_types: row.get(3),
+            kind: row.get(4),
+        })
+        .collect())
+}
+
+pub async fn list_sequences(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<SequenceInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT s.sequencename, s.schemaname, s.data_type::text,
+                    s.start_value::text, s.min_value::text, s.max_value::text,
+                    s.increment_by::text, s.cycle, s.cache_size::text,
+                    s.last_value::text, s.sequenceowner,
+                    (SELECT t.relname || '.' || a.attname
+                     FROM pg_depend d
+                     JOIN pg_class t ON t.oid = d.refobjid
+                     JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+                     WHERE d.objid = format('%I.%I', s.schemaname, s.sequencename)::regclass
+                        AND d.classid = 'pg_class'::regclass
+                        AND d.refclassid = 'pg_class'::regclass
+                        AND d.deptype IN ('a', 'i')
+                     LIMIT 1) as owned_by
+             FROM pg_sequences s
+             WHERE s.schemaname = $1
+             ORDER BY s.sequencename",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Sequence listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SequenceInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            data_type: row.get(2),
+            start_value: row.get(3),
+            min_value: row.get(4),
+            max_value: row.get(5),
+            increment_by: row.get(6),
+            cycle: row.get(7),
+            cache_size: row.get(8),
+            last_value: row.get(9),
+            owner: row.get(10),
+            owned_by: row.get(11),
+        })
+        .collect())
+}
+
+pub async fn list_materialized_views(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<MaterializedViewInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT m.matviewname, m.schemaname, m.matviewowner, m.ispopulated,
+                    pg_size_pretty(pg_total_relation_size(format('%I.%I', m.schemaname, m.matviewname)::regclass)),
+                    m.definition
+             FROM pg_matviews m
+             WHERE m.schemaname = $1
+             ORDER BY m.matviewname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Materialized view listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| MaterializedViewInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            owner: row.get(2),
+            is_populated: row.get(3),
+            size: row.get(4),
+            definition: row.get(5),
+        })
+        .collect())
+}
+
+pub async fn list_foreign_tables(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<ForeignTableInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT c.relname, n.nspname, s.srvname,
+                    COALESCE(ft.ftoptions, '{}'::text[])
+             FROM pg_foreign_table ft
+             JOIN pg_class c ON c.oid = ft.ftrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             JOIN pg_foreign_server s ON s.oid = ft.ftserver
+             WHERE n.nspname = $1
+             ORDER BY c.relname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Foreign table listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForeignTableInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            server: row.get(2),
+            options: row.get(3),
+        })
+        .collect())
+}
+
+pub async fn list_partitioned_tables(
+    client: &Arc<Client>,
+    schema: &str,
+) -> Result<Vec<PartitionedTableInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT c.relname, n.nspname, pg_get_partkeydef(c.oid),
+                    (SELECT COUNT(*) FROM pg_inherits i WHERE i.inhparent = c.oid)
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relkind = 'p'
+             ORDER BY c.relname",
             &[&schema],
         )
         .await
-        .map_err(|e| format!("Fonksiyon listesi alınamadı: {}", e))?;
+        .map_err(|e| format!("Bölümlenmiş tablo listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PartitionedTableInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            partition_key: row.get(2),
+            partition_count: row.get(3),
+        })
+        .collect())
+}
+
+pub async fn list_types(client: &Arc<Client>, schema: &str) -> Result<Vec<TypeInfo>, String> {
+    // Composite types backing tables/views (typrelid of a non-'c' relation) are skipped
+    let rows = client
+        .query(
+            "SELECT t.typname, n.nspname,
+                    CASE t.typtype
+                        WHEN 'c' THEN 'composite'
+                        WHEN 'd' THEN 'domain'
+                        WHEN 'e' THEN 'enum'
+                        ELSE 'range'
+                    END as kind,
+                    CASE t.typtype
+                        WHEN 'd' THEN format_type(t.typbasetype, t.typtypmod)
+                        WHEN 'r' THEN (SELECT format_type(r.rngsubtype, NULL)
+                                       FROM pg_range r WHERE r.rngtypid = t.oid)
+                    END as base_type,
+                    ARRAY(SELECT e.enumlabel::text FROM pg_enum e
+                          WHERE e.enumtypid = t.oid ORDER BY e.enumsortorder) as enum_values,
+                    ARRAY(SELECT quote_ident(a.attname) || ' ' || format_type(a.atttypid, a.atttypmod)
+                          FROM pg_attribute a
+                          WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped
+                          ORDER BY a.attnum) as attributes,
+                    ARRAY(SELECT 'NOT NULL' WHERE t.typnotnull
+                          UNION ALL
+                          SELECT pg_get_constraintdef(c.oid, true) FROM pg_constraint c
+                          WHERE c.contypid = t.oid AND c.contype = 'c') as constraints,
+                    t.typdefault,
+                    obj_description(t.oid, 'pg_type')
+             FROM pg_type t
+             JOIN pg_namespace n ON n.oid = t.typnamespace
+             LEFT JOIN pg_class rel ON rel.oid = t.typrelid
+             WHERE n.nspname = $1
+                AND t.typtype IN ('c', 'd', 'e', 'r')
+                AND (t.typtype <> 'c' OR rel.relkind = 'c')
+             ORDER BY t.typname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Tip listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TypeInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            kind: row.get(2),
+            base_type: row.get(3),
+            enum_values: row.get(4),
+            attributes: row.get(5),
+            constraints: row.get(6),
+            default_value: row.get(7),
+            comment: row.get(8),
+        })
+        .collect())
+}
+
+pub async fn list_triggers(client: &Arc<Client>, schema: &str) -> Result<Vec<TriggerInfo>, String> {
+    // tgtype bits: 1 row, 2 before, 4 insert, 8 delete, 16 update, 32 truncate, 64 instead
+    let rows = client
+        .query(
+            "SELECT t.tgname, n.nspname, c.relname,
+                    CASE WHEN t.tgtype & 64 <> 0 THEN 'INSTEAD OF'
+                         WHEN t.tgtype & 2 <> 0 THEN 'BEFORE'
+                         ELSE 'AFTER' END as timing,
+                    ARRAY(SELECT e FROM (VALUES
+                            (4, 'INSERT'), (16, 'UPDATE'), (8, 'DELETE'), (32, 'TRUNCATE')
+                          ) AS ev(bit, e)
+                          WHERE t.tgtype & ev.bit <> 0) as events,
+                    CASE WHEN t.tgtype & 1 <> 0 THEN 'ROW' ELSE 'STATEMENT' END as level,
+                    p.oid::regproc::text as function,
+                    t.tgenabled <> 'D' as enabled,
+                    pg_get_triggerdef(t.oid, true) as definition
+             FROM pg_trigger t
+             JOIN pg_class c ON c.oid = t.tgrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             JOIN pg_proc p ON p.oid = t.tgfoid
+             WHERE n.nspname = $1 AND NOT t.tgisinternal
+             ORDER BY c.relname, t.tgname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Trigger listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TriggerInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            table_name: row.get(2),
+            timing: row.get(3),
+            events: row.get(4),
+            level: row.get(5),
+            function: row.get(6),
+            enabled: row.get(7),
+            definition: row.get(8),
+        })
+        .collect())
+}
+
+pub async fn list_event_triggers(client: &Arc<Client>) -> Result<Vec<EventTriggerInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT e.evtname, e.evtevent, e.evtfoid::regproc::text,
+                    e.evtenabled <> 'D',
+                    COALESCE(e.evttags, '{}'::text[])
+             FROM pg_event_trigger e
+             ORDER BY e.evtname",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Event trigger listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| EventTriggerInfo {
+            name: row.get(0),
+            event: row.get(1),
+            function: row.get(2),
+            enabled: row.get(3),
+            tags: row.get(4),
+        })
+        .collect())
+}
+
+pub async fn list_policies(client: &Arc<Client>, schema: &str) -> Result<Vec<PolicyInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT p.policyname, p.schemaname, p.tablename,
+                    p.permissive = 'PERMISSIVE', p.cmd,
+                    p.roles::text[], p.qual, p.with_check
+             FROM pg_policies p
+             WHERE p.schemaname = $1
+             ORDER BY p.tablename, p.policyname",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Policy listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PolicyInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            table_name: row.get(2),
+            permissive: row.get(3),
+            command: row.get(4),
+            roles: row.get(5),
+            using_expr: row.get(6),
+            with_check: row.get(7),
+        })
+        .collect())
+}
+
+pub async fn list_rules(client: &Arc<Client>, schema: &str) -> Result<Vec<RuleInfo>, String> {
+    // The implicit "_RETURN" rule of every view is left out
+    let rows = client
+        .query(
+            "SELECT r.rulename, n.nspname, c.relname,
+                    CASE r.ev_type
+                        WHEN '1' THEN 'SELECT'
+                        WHEN '2' THEN 'UPDATE'
+                        WHEN '3' THEN 'INSERT'
+                        ELSE 'DELETE'
+                    END,
+                    r.is_instead,
+                    pg_get_ruledef(r.oid, true)
+             FROM pg_rewrite r
+             JOIN pg_class c ON c.oid = r.ev_class
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND r.rulename <> '_RETURN'
+             ORDER BY c.relname, r.rulename",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Rule listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RuleInfo {
+            name: row.get(0),
+            schema: row.get(1),
+            table_name: row.get(2),
+            event: row.get(3),
+            is_instead: row.get(4),
+            definition: row.get(5),
+        })
+        .collect())
+}
+
+pub async fn list_extensions(client: &Arc<Client>) -> Result<Vec<ExtensionInfo>, String> {
+    let rows = client
+        .query(
+            "SELECT e.extname, e.extversion, n.nspname, a.default_version,
+                    obj_description(e.oid, 'pg_extension')
+             FROM pg_extension e
+             JOIN pg_namespace n ON n.oid = e.extnamespace
+             LEFT JOIN pg_available_extensions a ON a.name = e.extname
+             ORDER BY e.extname",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Eklenti listesi alınamadı: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ExtensionInfo {
+            name: row.get(0),
+            version: row.get(1),
+            schema: row.get(2),
+            default_version: row.get(3),
+            comment: row.get(4),
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDataResult {
+    pub columns: Vec<TableColumnMeta>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub total_rows: i64,
+    /// False when `total_rows` is an estimate or hit the count cap
+    pub total_rows_exact: bool,
+    pub page: i64,
+    pub page_size: i64,
+    /// Keyset pagination: pass back as `after` to get the next page
+    pub next_cursor: Option<Vec<Option<String>>>,
+    pub row_identity: Option<RowIdentity>,
+    /// Values of `row_identity.columns` per row, as text
+    pub row_ids: Option<Vec<Vec<Option<String>>>>,
+    pub execution_time_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableColumnMeta {
+    pub name: String,
+    pub data_type: String,
+}
+
+pub fn pg_value_to_json(row: &tokio_postgres::Row, idx: usize) -> serde_json::Value {
+    // Columns of unsupported types are selected as ::text by the callers
+    row.try_get::<_, Option<PgValue>>(idx)
+        .ok()
+        .flatten()
+        .map(|v| v.0)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+pub(crate) fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Paging, sorting and filtering for `query_table_data`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableDataOptions {
+    pub page: i64,
+    pub page_size: i64,
+    /// Single-column sort kept for older callers; `sort` takes precedence
+    pub sort_column: Option<String>,
+    pub sort_direction: Option<String>,
+    pub sort: Option<Vec<SortSpec>>,
+    pub filters: Option<FilterGroup>,
+    pub count_mode: Option<CountMode>,
+    /// Upper bound for `CountMode::Capped`
+    pub count_cap: Option<i64>,
+    pub pagination: Option<PaginationMode>,
+    /// Keyset values of the last row of the previous page
+    pub after: Option<Vec<Option<String>>>,
+    /// Return `row_identity` and each row's identity values in `row_ids`
+    #[serde(default)]
+    pub with_row_ids: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CountMode {
+    /// `COUNT(*)` over the whole (filtered) table
+    Exact,
+    /// Planner estimate (`pg_class.reltuples` when unfiltered)
+    Estimated,
+    /// Counts at most `count_cap` rows
+    Capped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaginationMode {
+    Offset,
+    /// Seeks past the previous page on the sort columns plus a unique key
+    /// (primary key, unique NOT NULL index or `ctid`)
+    Keyset,
+}
+
+const DEFAULT_COUNT_CAP: i64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowIdentityStrategy {
+    PrimaryKey,
+    /// Unique index on NOT NULL columns
+    UniqueIndex,
+    /// Physical row location plus `xmin`, so a row that was updated or moved
+    /// since it was read no longer matches
+    Ctid,
+    None,
+}
+
+/// How rows of a table or view are identified for `update_row`/`delete_rows`;
+/// pass `columns` and `column_types` as the key columns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowIdentity {
+    pub strategy: RowIdentityStrategy,
+    pub columns: Vec<String>,
+    pub column_types: Vec<String>,
+    pub index_name: Option<String>,
+    pub can_insert: bool,
+    pub can_update: bool,
+    pub can_delete: bool,
+    /// Why rows can't be identified when `strategy` is `None`
+    pub reason: Option<String>,
+}
+
+pub async fn get_row_identity(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+) -> Result<RowIdentity, String> {
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    let map_err = |e: tokio_postgres::Error| format!("Satır kimliği belirlenemedi: {}", e);
+
+    // pg_relation_is_updatable bits: 4 = UPDATE, 8 = INSERT, 16 = DELETE
+    let rel = client
+        .query_one(
+            "SELECT c.oid, c.relkind::text, pg_relation_is_updatable(c.oid::regclass, false)
+             FROM pg_class c
+             WHERE c.oid = $1::text::regclass",
+            &[&qualified],
+        )
+        .await
+        .map_err(map_err)?;
+    let oid: u32 = rel.get(0);
+    let relkind: String = rel.get(1);
+    let updatable: i32 = rel.get(2);
+
+    // A view is keyed through its single base table, on columns it exposes
+    // under the same name
+    let key_source = if relkind == "v" {
+        client
+            .query_opt(
+                "SELECT MIN(d.refobjid)
+                 FROM pg_rewrite r
+                 JOIN pg_depend d ON d.classid = 'pg_rewrite'::regclass AND d.objid = r.oid
+                 JOIN pg_class t ON t.oid = d.refobjid AND t.relkind IN ('r', 'p')
+                 WHERE r.ev_class = $1
+                    AND d.refclassid = 'pg_class'::regclass
+                    AND d.refobjid <> $1
+                 HAVING COUNT(DISTINCT d.refobjid) = 1",
+                &[&oid],
+            )
+            .await
+            .map_err(map_err)?
+            .map(|row| row.get::<_, u32>(0))
+    } else {
+        Some(oid)
+    };
 
-    Ok(rows
-        .iter()
-        .map(|row| FunctionInfo {
-            name: row.get(0),
-            schema: row.get(1),
-            return_type: row.get(2),
-            argument_types: row.get(3),
-        })
-        .collect())
+    let mut identity = RowIdentity {
+        strategy: RowIdentityStrategy::None,
+        columns: Vec::new(),
+        column_types: Vec::new(),
+        index_name: None,
+        can_insert: updatable & 8 != 0,
+        can_update: updatable & 4 != 0,
+        can_delete: updatable & 16 != 0,
+        reason: None,
+    };
+
+    if let Some(source_oid) = key_source {
+        let rows = client
+            .query(
+                "SELECT i.indisprimary, ci.relname::text,
+                        array_agg(a.attname::text ORDER BY k.ord),
+                        array_agg(format_type(a.atttypid, a.atttypmod) ORDER BY k.ord),
+                        array_agg(a.attnum::int ORDER BY k.ord),
+                        bool_and(a.attnotnull)
+                 FROM pg_index i
+                 JOIN pg_class ci ON ci.oid = i.indexrelid
+                 CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY k(attnum, ord)
+                 JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+                 WHERE i.indrelid = $1
+                    AND i.indisunique AND i.indisvalid AND i.indpred IS NULL
+                    AND NOT 0 = ANY(i.indkey::int2[])
+                    AND k.ord <= i.indnkeyatts
+                 GROUP BY i.indexrelid, i.indisprimary, ci.relname
+                 ORDER BY i.indisprimary DESC, COUNT(*), ci.relname",
+                &[&source_oid],
+            )
+            .await
+            .map_err(map_err)?;
+
+        // Base columns the view passes through unchanged, with the view
+        // column showing each; taken from the origins the server reports
+        // for the view's defining query
+        let exposed: Option<Vec<(i32, String)>> = if relkind == "v" {
+            let definition: String = client
+                .query_one("SELECT pg_get_viewdef($1::oid)", &[&oid])
+                .await
+                .map_err(map_err)?
+                .get(0);
+            let stmt = client
+                .prepare(definition.trim().trim_end_matches(';'))
+                .await
+                .map_err(map_err)?;
+            let view_columns: Vec<String> = client
+                .query(
+                    "SELECT attname::text FROM pg_attribute
+                     WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped
+                     ORDER BY attnum",
+                    &[&oid],
+                )
+                .await
+                .map_err(map_err)?
+                .iter()
+                .map(|row| row.get(0))
+                .collect();
+            Some(
+                stmt.columns()
+                    .iter()
+                    .zip(view_columns)
+                    .filter(|(col, _)| col.table_oid() == Some(source_oid))
+                    .filter_map(|(col, name)| Some((i32::from(col.column_id()?), name)))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        // Key columns must reach the view under their own names, or the
+        // WHERE clause on the view would compare a different column
+        let candidate = rows.iter().find(|row| {
+            let is_primary: bool = row.get(0);
+            let not_null: bool = row.get(5);
+            let columns: Vec<String> = row.get(2);
+            let attnums: Vec<i32> = row.get(4);
+            let exposed = match &exposed {
+                Some(exposed) => attnums
+                    .iter()
+                    .zip(&columns)
+                    .all(|(n, name)| exposed.iter().any(|(m, view_name)| m == n && view_name == name)),
+                None => true,
+            };
+            (is_primary || not_null) && exposed
+        });
+
+        if let Some(row) = candidate {
+            identity.strategy = if row.get::<_, bool>(0) {
+                RowIdentityStrategy::PrimaryKey
+            } else {
+                RowIdentityStrategy::UniqueIndex
+            };
+            identity.index_name = Some(row.get(1));
+            identity.columns = row.get(2);
+            identity.column_types = row.get(3);
+            return Ok(identity);
+        }
+    }
+
+    match relkind.as_str() {
+        "r" => {
+            identity.strategy = RowIdentityStrategy::Ctid;
+            identity.columns = vec!["ctid".to_string(), "xmin".to_string()];
+            identity.column_types = vec!["tid".to_string(), "xid".to_string()];
+        }
+        // ctid is only unique within one partition
+        "p" => {
+            identity.strategy = RowIdentityStrategy::Ctid;
+            identity.columns = vec!["tableoid".to_string(), "ctid".to_string(), "xmin".to_string()];
+            identity.column_types = vec!["oid".to_string(), "tid".to_string(), "xid".to_string()];
+        }
+        "v" => {
+            identity.reason = Some(
+                "Görünüm tek bir tablonun birincil veya benzersiz anahtarını içermiyor".to_string(),
+            );
+        }
+        _ => {
+            identity.reason = Some("Bu nesne türünde satırlar tanımlanamıyor".to_string());
+        }
+    }
+    Ok(identity)
 }
 
-pub async fn list_sequences(
+/// Primary key columns (in key order) and NOT NULL columns of a table.
+pub(crate) async fn table_key_info(
     client: &Arc<Client>,
-    schema: &str,
-) -> Result<Vec<SequenceInfo>, String> {
+    qualified: &str,
+) -> Result<(Vec<String>, Vec<String>), String> {
     let rows = client
         .query(
-            "SELECT sequence_name, sequence_schema
-             FROM information_schema.sequences
-             WHERE sequence_schema = $1
-             ORDER BY sequence_name",
-            &[&schema],
+            "SELECT a.attname,
+                    COALESCE(array_position(i.indkey::int2[], a.attnum), 0) as pk_position,
+                    a.attnotnull
+             FROM pg_attribute a
+             LEFT JOIN pg_index i ON i.indrelid = a.attrelid AND i.indisprimary
+             WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped",
+            &[&qualified],
         )
         .await
-        .map_err(|e| format!("Sequence listesi alınamadı: {}", e))?;
+        .map_err(|e| format!("Anahtar bilgisi alınamadı: {}", e))?;
 
-    Ok(rows
+    let mut pk: Vec<(i32, String)> = rows
         .iter()
-        .map(|row| SequenceInfo {
-            name: row.get(0),
-            schema: row.get(1),
-        })
-        .collect())
-}
+        .filter(|r| r.get::<_, i32>(1) > 0)
+        .map(|r| (r.get(1), r.get(0)))
+        .collect();
+    pk.sort();
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TableDataResult {
-    pub columns: Vec<TableColumnMeta>,
-    pub rows: Vec<Vec<serde_json::Value>>,
-    pub total_rows: i64,
-    pub page: i64,
-    pub page_size: i64,
-    pub execution_time_ms: u128,
+    let not_null = rows
+        .iter()
+        .filter(|r| r.get::<_, bool>(2))
+        .map(|r| r.get(0))
+        .collect();
+
+    Ok((pk.into_iter().map(|(_, name)| name).collect(), not_null))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TableColumnMeta {
-    pub name: String,
-    pub data_type: String,
+fn plan_rows(plan: &serde_json::Value) -> Option<i64> {
+    plan.get(0)?
+        .get("Plan")?
+        .get("Plan Rows")?
+        .as_f64()
+        .map(|v| v as i64)
 }
 
-pub fn pg_value_to_json(row: &tokio_postgres::Row, idx: usize, col_type: &Type) -> serde_json::Value {
-    // Try to get the value as various types, fallback to text
-    match *col_type {
-        Type::BOOL => row
-            .try_get::<_, Option<bool>>(idx)
-            .ok()
-            .flatten()
-            .map(serde_json::Value::Bool)
-            .unwrap_or(serde_json::Value::Null),
-        Type::INT2 => row
-            .try_get::<_, Option<i16>>(idx)
-            .ok()
-            .flatten()
-            .map(|v| serde_json::Value::Number(v.into()))
-            .unwrap_or(serde_json::Value::Null),
-        Type::INT4 => row
-            .try_get::<_, Option<i32>>(idx)
-            .ok()
-            .flatten()
-            .map(|v| serde_json::Value::Number(v.into()))
-            .unwrap_or(serde_json::Value::Null),
-        Type::INT8 => row
-            .try_get::<_, Option<i64>>(idx)
-            .ok()
-            .flatten()
-            .map(|v| serde_json::Value::Number(v.into()))
-            .unwrap_or(serde_json::Value::Null),
-        Type::FLOAT4 => row
-            .try_get::<_, Option<f32>>(idx)
-            .ok()
-            .flatten()
-            .and_then(|v| serde_json::Number::from_f64(v as f64))
-            .map(serde_json::Value::Number)
-            .unwrap_or(serde_json::Value::Null),
-        Type::FLOAT8 => row
-            .try_get::<_, Option<f64>>(idx)
-            .ok()
-            .flatten()
-            .and_then(|v| serde_json::Number::from_f64(v))
-            .map(serde_json::Value::Number)
-            .unwrap_or(serde_json::Value::Null),
-        Type::JSON | Type::JSONB => row
-            .try_get::<_, Option<serde_json::Value>>(idx)
-            .ok()
-            .flatten()
-            .unwrap_or(serde_json::Value::Null),
-        _ => {
-            // Fallback: try as String
-            row.try_get::<_, Option<String>>(idx)
-                .ok()
-                .flatten()
-                .map(serde_json::Value::String)
-                .unwrap_or(serde_json::Value::Null)
+/// Returns the row count and whether it is exact.
+async fn count_table_rows(
+    client: &Arc<Client>,
+    qualified: &str,
+    where_clause: &str,
+    params: &SqlParams,
+    mode: CountMode,
+    cap: i64,
+) -> Result<(i64, bool), String> {
+    match mode {
+        CountMode::Exact => {
+            let stmt = client
+                .prepare_typed(
+                    &format!("SELECT COUNT(*) FROM {} {}", qualified, where_clause),
+                    params.types(),
+                )
+                .await
+                .map_err(|e| format_db_error(&e))?;
+            let row = client
+                .query_one(&stmt, &params.refs())
+                .await
+                .map_err(|e| format!("Satır sayısı alınamadı: {}", e))?;
+            Ok((row.get(0), true))
+        }
+        CountMode::Capped => {
+            let stmt = client
+                .prepare_typed(
+                    &format!(
+                        "SELECT COUNT(*) FROM (SELECT 1 FROM {} {} LIMIT {}) s",
+                        qualified,
+                        where_clause,
+                        cap + 1
+                    ),
+                    params.types(),
+                )
+                .await
+                .map_err(|e| format_db_error(&e))?;
+            let row = client
+                .query_one(&stmt, &params.refs())
+                .await
+                .map_err(|e| format!("Satır sayısı alınamadı: {}", e))?;
+            let count: i64 = row.get(0);
+            Ok((count.min(cap), count <= cap))
+        }
+        CountMode::Estimated => {
+            if where_clause.is_empty() {
+                // A never analyzed table has reltuples = -1 from PG14 on;
+                // before that it shows 0 rows on 0 pages
+                let row = client
+                    .query_one(
+                        "SELECT reltuples::bigint,
+                                reltuples < 0 OR (reltuples = 0 AND relpages = 0
+                                    AND current_setting('server_version_num')::int < 140000)
+                         FROM pg_class WHERE oid = $1::text::regclass",
+                        &[&qualified],
+                    )
+                    .await
+                    .map_err(|e| format!("Satır tahmini alınamadı: {}", e))?;
+                let unknown: bool = row.get(1);
+                if !unknown {
+                    return Ok((row.get(0), false));
+                }
+            }
+
+            let stmt = client
+                .prepare_typed(
+                    &format!("EXPLAIN (FORMAT JSON) SELECT 1 FROM {} {}", qualified, where_clause),
+                    params.types(),
+                )
+                .await
+                .map_err(|e| format_db_error(&e))?;
+            let row = client
+                .query_one(&stmt, &params.refs())
+                .await
+                .map_err(|e| format!("Satır tahmini alınamadı: {}", e))?;
+            let plan: serde_json::Value = row.get(0);
+            let estimate = plan_rows(&plan).ok_or("Satır tahmini okunamadı")?;
+            Ok((estimate, false))
         }
     }
 }
@@ -436,93 +1484,208 @@ pub async fn query_table_data(
     client: &Arc<Client>,
     schema: &str,
     table: &str,
-    page: i64,
-    page_size: i64,
-    sort_column: Option<String>,
-    sort_direction: Option<String>,
+    options: TableDataOptions,
 ) -> Result<TableDataResult, String> {
     let start = Instant::now();
+    let TableDataOptions { page, page_size, .. } = options;
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
 
-    // Get total count
-    let count_sql = format!(
-        "SELECT COUNT(*) FROM \"{}\".\"{}\"",
-        schema, table
-    );
-    let count_row = client
-        .query_one(&count_sql, &[])
+    // Prepare first to get column metadata even with no rows, and to
+    // select types we can't decode as text
+    let meta_stmt = client
+        .prepare(&format!("SELECT * FROM {} LIMIT 0", qualified))
         .await
-        .map_err(|e| format!("Satır sayısı alınamadı: {}", e))?;
-    let total_rows: i64 = count_row.get(0);
-
-    // Build query
-    let offset = (page - 1) * page_size;
-    let order_clause = match (&sort_column, &sort_direction) {
-        (Some(col), Some(dir)) => {
-            let direction = if dir == "desc" { "DESC" } else { "ASC" };
-            format!("ORDER BY \"{}\" {} NULLS LAST", col, direction)
+        .map_err(|e| format!("Kolon bilgisi alınamadı: {}", e))?;
+
+    let columns: Vec<TableColumnMeta> = meta_stmt
+        .columns()
+        .iter()
+        .map(|col| TableColumnMeta {
+            name: col.name().to_string(),
+            data_type: col.type_().name().to_string(),
+        })
+        .collect();
+
+    let mut params = SqlParams::default();
+    let where_clause =
+        match filter::build_filter_sql(options.filters.as_ref(), meta_stmt.columns(), &mut params)? {
+            Some(cond) => format!("WHERE {}", cond),
+            None => String::new(),
+        };
+
+    let sort = match (options.sort, options.sort_column) {
+        (Some(sort), _) => sort,
+        (None, Some(column)) => vec![SortSpec {
+            column,
+            direction: if options.sort_direction.as_deref() == Some("desc") {
+                SortDirection::Desc
+            } else {
+                SortDirection::Asc
+            },
+        }],
+        (None, None) => Vec::new(),
+    };
+
+    let (total_rows, total_rows_exact) = count_table_rows(
+        client,
+        &qualified,
+        &where_clause,
+        &params,
+        options.count_mode.unwrap_or(CountMode::Exact),
+        options.count_cap.unwrap_or(DEFAULT_COUNT_CAP).max(1),
+    )
+    .await?;
+
+    let keyset = options.pagination == Some(PaginationMode::Keyset);
+    let identity = if keyset || options.with_row_ids {
+        Some(get_row_identity(client, schema, table).await?)
+    } else {
+        None
+    };
+
+    // Keyset: order by the sort columns, then a unique key as tie-breaker so
+    // rows that tie at a page boundary are neither skipped nor repeated
+    let mut key_columns: Vec<KeyColumn> = Vec::new();
+    if let (true, Some(identity)) = (keyset, identity.as_ref()) {
+        let (_, not_null) = table_key_info(client, &qualified).await?;
+        for s in &sort {
+            let column = filter::find_column(meta_stmt.columns(), &s.column)?;
+            key_columns.push(KeyColumn {
+                name: column.name().to_string(),
+                cast: type_cast_name(column.type_()),
+                direction: s.direction,
+                not_null: not_null.contains(&s.column),
+            });
+        }
+
+        let unique_key: Vec<(String, String)> = match identity.strategy {
+            RowIdentityStrategy::PrimaryKey | RowIdentityStrategy::UniqueIndex => identity
+                .columns
+                .iter()
+                .map(|c| {
+                    filter::find_column(meta_stmt.columns(), c)
+                        .map(|col| (c.clone(), type_cast_name(col.type_())))
+                })
+                .collect::<Result<_, _>>()?,
+            // xmin only guards edits; the location alone is unique
+            RowIdentityStrategy::Ctid => identity
+                .columns
+                .iter()
+                .zip(identity.column_types.iter())
+                .filter(|(c, _)| c.as_str() != "xmin")
+                .map(|(c, t)| (c.clone(), t.clone()))
+                .collect(),
+            RowIdentityStrategy::None => {
+                return Err(
+                    "Keyset sayfalama için benzersiz bir anahtar gerekli; sayfa numarasıyla sayfalama kullanın"
+                        .to_string(),
+                )
+            }
+        };
+        for (name, cast) in unique_key {
+            if !key_columns.iter().any(|k| k.name == name) {
+                key_columns.push(KeyColumn {
+                    name,
+                    cast,
+                    direction: SortDirection::Asc,
+                    not_null: true,
+                });
+            }
         }
-        _ => String::new(),
+    }
+    let order_clause = if keyset {
+        filter::build_keyset_order_by(&key_columns)
+    } else {
+        filter::build_order_by(&sort, meta_stmt.columns())?
+    };
+
+    let mut conditions: Vec<String> = where_clause
+        .strip_prefix("WHERE ")
+        .map(|c| vec![c.to_string()])
+        .unwrap_or_default();
+    if let (true, Some(after)) = (keyset, options.after.as_ref()) {
+        conditions.push(filter::build_keyset_predicate(&key_columns, after, &mut params)?);
+    }
+    let page_where = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
     };
 
+    let row_identity = if options.with_row_ids { identity } else { None };
+
+    // Build query; keyset and row identity values are selected as text
+    // after the table columns
+    let offset = if keyset { 0 } else { (page - 1) * page_size };
+    let mut select_list = value::select_list(meta_stmt.columns(), None);
+    for (i, key) in key_columns.iter().enumerate() {
+        select_list.push_str(&format!(
+            ", {}::text AS dbleaf_key_{}",
+            quote_ident(&key.name),
+            i
+        ));
+    }
+    let id_columns = row_identity.as_ref().map(|r| r.columns.as_slice()).unwrap_or(&[]);
+    for (i, column) in id_columns.iter().enumerate() {
+        select_list.push_str(&format!(", {}::text AS dbleaf_id_{}", quote_ident(column), i));
+    }
     let data_sql = format!(
-        "SELECT * FROM \"{}\".\"{}\" {} LIMIT {} OFFSET {}",
-        schema, table, order_clause, page_size, offset
+        "SELECT {} FROM {} {} {} LIMIT {} OFFSET {}",
+        select_list, qualified, page_where, order_clause, page_size, offset
     );
 
+    let data_stmt = client
+        .prepare_typed(&data_sql, params.types())
+        .await
+        .map_err(|e| format_db_error(&e))?;
     let rows = client
-        .query(&data_sql, &[])
+        .query(&data_stmt, &params.refs())
         .await
         .map_err(|e| format!("Veri sorgulanamadı: {}", e))?;
 
-    // Extract column info from first row or statement
-    let columns: Vec<TableColumnMeta> = if !rows.is_empty() {
-        rows[0]
-            .columns()
-            .iter()
-            .map(|col| TableColumnMeta {
-                name: col.name().to_string(),
-                data_type: col.type_().name().to_string(),
-            })
-            .collect()
-    } else {
-        // Use prepare to get column metadata even with no rows
-        let stmt = client
-            .prepare(&format!(
-                "SELECT * FROM \"{}\".\"{}\" LIMIT 0",
-                schema, table
-            ))
-            .await
-            .map_err(|e| format!("Kolon bilgisi alınamadı: {}", e))?;
-
-        stmt.columns()
-            .iter()
-            .map(|col| TableColumnMeta {
-                name: col.name().to_string(),
-                data_type: col.type_().name().to_string(),
-            })
-            .collect()
-    };
-
     // Convert rows to JSON values
+    let column_count = columns.len();
     let json_rows: Vec<Vec<serde_json::Value>> = rows
         .iter()
         .map(|row| {
-            row.columns()
-                .iter()
-                .enumerate()
-                .map(|(idx, col)| pg_value_to_json(row, idx, col.type_()))
+            (0..column_count)
+                .map(|idx| pg_value_to_json(row, idx))
                 .collect()
         })
         .collect();
 
+    let next_cursor = match rows.last() {
+        Some(last) if keyset && rows.len() as i64 == page_size => Some(
+            (0..key_columns.len())
+                .map(|i| last.get::<_, Option<String>>(column_count + i))
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    let id_offset = column_count + key_columns.len();
+    let row_ids = row_identity.as_ref().map(|_| {
+        rows.iter()
+            .map(|row| {
+                (0..id_columns.len())
+                    .map(|i| row.get::<_, Option<String>>(id_offset + i))
+                    .collect()
+            })
+            .collect()
+    });
+
     let execution_time_ms = start.elapsed().as_millis();
 
     Ok(TableDataResult {
         columns,
         rows: json_rows,
         total_rows,
+        total_rows_exact,
         page,
         page_size,
+        next_cursor,
+        row_identity,
+        row_ids,
         execution_time_ms,
     })
 }
@@ -535,9 +1698,14 @@ pub struct ExecuteQueryResult {
     pub execution_time_ms: u128,
     pub is_select: bool,
     pub affected_rows: Option<u64>,
+    /// Set while more rows are waiting on a server-side cursor
+    pub result_handle: Option<String>,
+    pub has_more: bool,
+    /// True when the row cap cut the result short
+    pub truncated: bool,
 }
 
-fn format_db_error(e: &tokio_postgres::Error) -> String {
+pub(crate) fn format_db_error(e: &tokio_postgres::Error) -> String {
     if let Some(db_err) = e.as_db_error() {
         let mut msg = format!("HATA: {}", db_err.message());
 
@@ -566,13 +1734,24 @@ fn format_db_error(e: &tokio_postgres::Error) -> String {
     }
 }
 
+/// Runs a statement and returns its whole result. With `max_rows` set, only
+/// that many rows are read off the wire and the rest are discarded.
 pub async fn execute_query(
     client: &Arc<Client>,
     sql: &str,
+    params: &[QueryParam],
+    max_rows: Option<usize>,
 ) -> Result<ExecuteQueryResult, String> {
     let start = Instant::now();
     let trimmed = sql.trim();
 
+    // Placeholders are only rewritten when values are supplied
+    let bound = if params.is_empty() {
+        None
+    } else {
+        Some(bind_query(client, trimmed, params).await?)
+    };
+
     // Check if it's a SELECT-like query (returns rows)
     let upper = trimmed.to_uppercase();
     let is_select = upper.starts_with("SELECT")
@@ -583,35 +1762,58 @@ pub async fn execute_query(
         || upper.starts_with("EXPLAIN");
 
     if is_select {
-        let rows = client
-            .query(trimmed, &[])
+        let (query_sql, param_types) = match &bound {
+            Some(b) => (b.sql.as_str(), b.param_types()),
+            None => (trimmed, Vec::new()),
+        };
+        let stmt = client
+            .prepare_typed(query_sql, &param_types)
             .await
             .map_err(|e| format_db_error(&e))?;
 
-        let columns: Vec<TableColumnMeta> = if !rows.is_empty() {
-            rows[0]
-                .columns()
-                .iter()
-                .map(|col| TableColumnMeta {
-                    name: col.name().to_string(),
-                    data_type: col.type_().name().to_string(),
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        let json_rows: Vec<Vec<serde_json::Value>> = rows
+        let columns: Vec<TableColumnMeta> = stmt
+            .columns()
             .iter()
-            .map(|row| {
-                row.columns()
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, col)| pg_value_to_json(row, idx, col.type_()))
-                    .collect()
+            .map(|col| TableColumnMeta {
+                name: col.name().to_string(),
+                data_type: col.type_().name().to_string(),
             })
             .collect();
 
+        // Re-select undecodable columns as text
+        let stmt = match value::text_cast_query(query_sql, stmt.columns())? {
+            Some(wrapped) => client
+                .prepare_typed(&wrapped, &param_types)
+                .await
+                .map_err(|_| value::undecodable_error(stmt.columns()))?,
+            None => stmt,
+        };
+
+        let values = match &bound {
+            Some(b) => b.params(),
+            None => Vec::new(),
+        };
+        let stream = client
+            .query_raw(&stmt, values)
+            .await
+            .map_err(|e| format_db_error(&e))?;
+        futures_util::pin_mut!(stream);
+
+        let cap = max_rows.unwrap_or(usize::MAX);
+        let mut json_rows: Vec<Vec<serde_json::Value>> = Vec::new();
+        let mut truncated = false;
+        while let Some(row) = stream.try_next().await.map_err(|e| format_db_error(&e))? {
+            if json_rows.len() >= cap {
+                truncated = true;
+                break;
+            }
+            json_rows.push(
+                (0..row.len())
+                    .map(|idx| pg_value_to_json(&row, idx))
+                    .collect(),
+            );
+        }
+
         let row_count = json_rows.len();
         let execution_time_ms = start.elapsed().as_millis();
 
@@ -622,12 +1824,22 @@ pub async fn execute_query(
             execution_time_ms,
             is_select: true,
             affected_rows: None,
+            result_handle: None,
+            has_more: false,
+            truncated,
         })
     } else {
-        let affected = client
-            .execute(trimmed, &[])
-            .await
-            .map_err(|e| format_db_error(&e))?;
+        let affected = match &bound {
+            Some(b) => {
+                let stmt = client
+                    .prepare_typed(&b.sql, &b.param_types())
+                    .await
+                    .map_err(|e| format_db_error(&e))?;
+                client.execute(&stmt, &b.params()).await
+            }
+            None => client.execute(trimmed, &[]).await,
+        }
+        .map_err(|e| format_db_error(&e))?;
 
         let execution_time_ms = start.elapsed().as_millis();
 
@@ -638,6 +1850,9 @@ pub async fn execute_query(
             execution_time_ms,
             is_select: false,
             affected_rows: Some(affected),
+            result_handle: None,
+            has_more: false,
+            truncated: false,
         })
     }
 }
@@ -717,67 +1932,45 @@ pub async fn update_row(
     update_columns: Vec<String>,
     update_values: Vec<Option<String>>,
     update_types: Vec<String>,
-) -> Result<u64, String> {
-    if pk_columns.is_empty() {
-        return Err("Birincil anahtar bulunamadı".to_string());
-    }
-    if update_columns.is_empty() {
-        return Err("Güncellenecek kolon bulunamadı".to_string());
-    }
-
-    let mut param_idx = 1usize;
-
-    let set_clauses: Vec<String> = update_columns
-        .iter()
-        .zip(update_types.iter())
-        .map(|(col, typ)| {
-            let clause = format!("\"{}\" = ${}::{}", col, param_idx, typ);
-            param_idx += 1;
-            clause
-        })
-        .collect();
-
-    let where_clauses: Vec<String> = pk_columns
-        .iter()
-        .zip(pk_types.iter())
-        .map(|(col, typ)| {
-            let clause = format!("\"{}\" = ${}::{}", col, param_idx, typ);
-            param_idx += 1;
-            clause
-        })
-        .collect();
-
-    let sql = format!(
-        "UPDATE \"{}\".\"{}\" SET {} WHERE {}",
-        schema,
-        table,
-        set_clauses.join(", "),
-        where_clauses.join(" AND ")
-    );
-
-    // Combine: update values first, then PK values
-    let all_values: Vec<Option<String>> = update_values
-        .into_iter()
-        .chain(pk_values.into_iter())
-        .collect();
-
-    let param_types: Vec<Type> = vec![Type::TEXT; all_values.len()];
-    let stmt = client
-        .prepare_typed(&sql, &param_types)
-        .await
-        .map_err(|e| format_db_error(&e))?;
-
-    let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = all_values
-        .iter()
-        .map(|v| v as &(dyn tokio_postgres::types::ToSql + Sync))
-        .collect();
+    expected: Option<RowExpectation>,
+) -> Result<UpdateRowResult, String> {
+    let key = RowKey {
+        columns: pk_columns,
+        values: pk_values,
+        types: pk_types,
+    };
+    let change = RowChange::Update {
+        key: key.clone(),
+        columns: update_columns,
+        values: update_values,
+        column_types: update_types,
+        expected,
+    };
+    let shape = changes::table_shape(client, schema, table).await?;
+    let returned =
+        changes::execute_change_returning(client, &shape, schema, table, &change).await?;
+
+    // With an expectation, no match means the row changed since it was loaded
+    let conflict = match &change {
+        RowChange::Update {
+            expected: Some(_), ..
+        } if returned.affected_rows == 0 => {
+            Some(changes::fetch_conflict(client, &shape, schema, table, &key).await?)
+        }
+        _ if returned.affected_rows == 0 && change_uses_ctid(&change) => {
+            return Err(changes::ROW_MOVED_ERROR.to_string());
+        }
+        _ => None,
+    };
 
-    let affected = client
-        .execute(&stmt, &params)
-        .await
-        .map_err(|e| format_db_error(&e))?;
+    Ok(UpdateRowResult { returned, conflict })
+}
 
-    Ok(affected)
+fn change_uses_ctid(change: &RowChange) -> bool {
+    match change {
+        RowChange::Update { key, .. } | RowChange::Delete { key } => key.uses_ctid(),
+        RowChange::Insert { .. } => false,
+    }
 }
 
 pub async fn insert_row(
@@ -787,44 +1980,14 @@ pub async fn insert_row(
     columns: Vec<String>,
     values: Vec<Option<String>>,
     column_types: Vec<String>,
-) -> Result<u64, String> {
-    if columns.is_empty() {
-        return Err("Kolon belirtilmedi".to_string());
-    }
-
-    let col_list: Vec<String> = columns.iter().map(|c| format!("\"{}\"", c)).collect();
-
-    let val_placeholders: Vec<String> = column_types
-        .iter()
-        .enumerate()
-        .map(|(i, typ)| format!("${}::{}", i + 1, typ))
-        .collect();
-
-    let sql = format!(
-        "INSERT INTO \"{}\".\"{}\" ({}) VALUES ({})",
-        schema,
-        table,
-        col_list.join(", "),
-        val_placeholders.join(", ")
-    );
-
-    let param_types: Vec<Type> = vec![Type::TEXT; values.len()];
-    let stmt = client
-        .prepare_typed(&sql, &param_types)
-        .await
-        .map_err(|e| format_db_error(&e))?;
-
-    let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = values
-        .iter()
-        .map(|v| v as &(dyn tokio_postgres::types::ToSql + Sync))
-        .collect();
-
-    let affected = client
-        .execute(&stmt, &params)
-        .await
-        .map_err(|e| format_db_error(&e))?;
-
-    Ok(affected)
+) -> Result<ReturnedRows, String> {
+    let change = RowChange::Insert {
+        columns,
+        values,
+        column_types,
+    };
+    let shape = changes::table_shape(client, schema, table).await?;
+    changes::execute_change_returning(client, &shape, schema, table, &change).await
 }
 
 pub async fn delete_rows(
@@ -842,48 +2005,40 @@ pub async fn delete_rows(
         return Err("Silinecek satır bulunamadı".to_string());
     }
 
-    let mut total_affected: u64 = 0;
-
-    // Delete one row at a time for simplicity and safety
-    for pk_values in &pk_value_sets {
-        if pk_values.len() != pk_columns.len() {
-            return Err("PK değer sayısı kolon sayısıyla eşleşmiyor".to_string());
-        }
-
-        let where_clauses: Vec<String> = pk_columns
-            .iter()
-            .zip(pk_types.iter())
-            .enumerate()
-            .map(|(i, (col, typ))| format!("\"{}\" = ${}::{}", col, i + 1, typ))
-            .collect();
-
-        let sql = format!(
-            "DELETE FROM \"{}\".\"{}\" WHERE {}",
-            schema,
-            table,
-            where_clauses.join(" AND ")
-        );
-
-        let param_types: Vec<Type> = vec![Type::TEXT; pk_values.len()];
-        let stmt = client
-            .prepare_typed(&sql, &param_types)
-            .await
-            .map_err(|e| format_db_error(&e))?;
+    // Delete one row at a time, in one transaction so a row that moved
+    // under a ctid key leaves the rest of the selection in place
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
 
-        let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = pk_values
-            .iter()
-            .map(|v| v as &(dyn tokio_postgres::types::ToSql + Sync))
-            .collect();
+    let result = async {
+        let mut total_affected: u64 = 0;
+        for pk_values in pk_value_sets {
+            let change = RowChange::Delete {
+                key: RowKey {
+                    columns: pk_columns.clone(),
+                    values: pk_values,
+                    types: pk_types.clone(),
+                },
+            };
+            let (sql, values) = changes::build_change_sql(schema, table, &change)?;
+            let affected = changes::execute_change(client, &sql, &values).await?;
+            if affected == 0 && change_uses_ctid(&change) {
+                return Err(changes::ROW_MOVED_ERROR.to_string());
+            }
 
-        let affected = client
-            .execute(&stmt, &params)
-            .await
-            .map_err(|e| format_db_error(&e))?;
+            total_affected += affected;
+        }
+        Ok(total_affected)
+    }
+    .await;
 
-        total_affected += affected;
+    if result.is_err() {
+        client.execute("ROLLBACK", &[]).await.ok();
+    } else if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
     }
 
-    Ok(total_affected)
+    result
 }
 
 pub async fn export_table_data(
@@ -892,7 +2047,16 @@ pub async fn export_table_data(
     table: &str,
     format: &str,
 ) -> Result<String, String> {
-    let sql = format!("SELECT * FROM \"{}\".\"{}\"", schema, table);
+    let meta_stmt = client
+        .prepare(&format!("SELECT * FROM \"{}\".\"{}\" LIMIT 0", schema, table))
+        .await
+        .map_err(|e| format!("Veri alınamadı: {}", e))?;
+    let sql = format!(
+        "SELECT {} FROM \"{}\".\"{}\"",
+        value::select_list(meta_stmt.columns(), None),
+        schema,
+        table
+    );
     let rows = client
         .query(&sql, &[])
         .await
@@ -907,7 +2071,6 @@ pub async fn export_table_data(
     }
 
     let columns: Vec<&str> = rows[0].columns().iter().map(|c| c.name()).collect();
-    let col_types: Vec<&Type> = rows[0].columns().iter().map(|c| c.type_()).collect();
 
     if format == "csv" {
         let mut csv = columns.join(",");
@@ -918,7 +2081,7 @@ pub async fn export_table_data(
                 .iter()
                 .enumerate()
                 .map(|(idx, _)| {
-                    let val = pg_value_to_json(row, idx, col_types[idx]);
+                    let val = pg_value_to_json(row, idx);
                     match val {
                         serde_json::Value::Null => String::new(),
                         serde_json::Value::String(s) => {
@@ -945,7 +2108,7 @@ pub async fn export_table_data(
                 for (idx, col_name) in columns.iter().enumerate() {
                     obj.insert(
                         col_name.to_string(),
-                        pg_value_to_json(row, idx, col_types[idx]),
+                        pg_value_to_json(row, idx),
                     );
                 }
                 serde_json::Value::Object(obj)
@@ -1072,20 +2235,37 @@ fn find_max_actual_time(node: &ExplainPlanNode) -> f64 {
 pub async fn explain_query(
     client: &Arc<Client>,
     sql: &str,
+    params: &[QueryParam],
 ) -> Result<ExplainResult, String> {
     let start = Instant::now();
     let trimmed = sql.trim();
 
+    let bound = if params.is_empty() {
+        None
+    } else {
+        Some(bind_query(client, trimmed, params).await?)
+    };
+
     // Wrap in transaction so DML queries have no side effects
     client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
 
-    let explain_sql = format!("EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS, VERBOSE) {}", trimmed);
+    let explain_sql = format!(
+        "EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS, VERBOSE) {}",
+        bound.as_ref().map(|b| b.sql.as_str()).unwrap_or(trimmed)
+    );
 
     let result = async {
-        let rows = client
-            .query(&explain_sql, &[])
-            .await
-            .map_err(|e| format_db_error(&e))?;
+        let rows = match &bound {
+            Some(b) => {
+                let stmt = client
+                    .prepare_typed(&explain_sql, &b.param_types())
+                    .await
+                    .map_err(|e| format_db_error(&e))?;
+                client.query(&stmt, &b.params()).await
+            }
+            None => client.query(&explain_sql, &[]).await,
+        }
+        .map_err(|e| format_db_error(&e))?;
 
         if rows.is_empty() {
             return Err("EXPLAIN sonucu boş döndü".to_string());
@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::filter::{FilterCondition, FilterGroup, FilterLogic, FilterNode, FilterOperator};
+use super::queries::{
+    self, format_db_error, quote_ident, CountMode, TableDataOptions, TableDataResult,
+};
+
+// ── Foreign key navigation ──────────────────────────────────────────────
+
+pub const DEFAULT_REFERENCING_PAGE_SIZE: i64 = 20;
+
+/// One foreign key constraint with its columns in key order, so composite
+/// keys pair up by position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignKeyLink {
+    pub constraint_name: String,
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableReferences {
+    /// Keys on this table pointing to other tables
+    pub outgoing: Vec<ForeignKeyLink>,
+    /// Keys on other tables (or this one) pointing here
+    pub incoming: Vec<ForeignKeyLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferencedRow {
+    pub foreign_key: ForeignKeyLink,
+    /// Filters selecting the row in the referenced table, to open it in a grid
+    pub filters: FilterGroup,
+    /// None when a key column is NULL, so nothing is referenced
+    pub data: Option<TableDataResult>,
+}
+
+/// A row of the referenced table: column names and their values as text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowValues {
+    pub columns: Vec<String>,
+    pub values: Vec<Option<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReferencingRowsOptions {
+    /// Only this constraint, e.g. to load the next page of one group
+    #[serde(default)]
+    pub constraint_name: Option<String>,
+    #[serde(default)]
+    pub page: Option<i64>,
+    #[serde(default)]
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferencingGroup {
+    pub foreign_key: ForeignKeyLink,
+    pub filters: FilterGroup,
+    pub data: Option<TableDataResult>,
+}
+
+fn action_name(code: &str) -> String {
+    match code {
+        "r" => "RESTRICT",
+        "c" => "CASCADE",
+        "n" => "SET NULL",
+        "d" => "SET DEFAULT",
+        _ => "NO ACTION",
+    }
+    .to_string()
+}
+
+/// Constraints cloned onto partitions are skipped (`conislocal`), so a key
+/// declared on a partitioned table shows up once.
+async fn foreign_key_links(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    incoming: bool,
+) -> Result<Vec<ForeignKeyLink>, String> {
+    let side = if incoming { "c.confrelid" } else { "c.conrelid" };
+    let sql = format!(
+        "SELECT c.conname::text, sn.nspname::text, sc.relname::text,
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(c.conkey) WITH ORDINALITY k(attnum, n)
+                      JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                      ORDER BY k.n),
+                tn.nspname::text, tc.relname::text,
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(c.confkey) WITH ORDINALITY k(attnum, n)
+                      JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                      ORDER BY k.n),
+                c.confupdtype::text, c.confdeltype::text
+         FROM pg_constraint c
+         JOIN pg_class sc ON sc.oid = c.conrelid
+         JOIN pg_namespace sn ON sn.oid = sc.relnamespace
+         JOIN pg_class tc ON tc.oid = c.confrelid
+         JOIN pg_namespace tn ON tn.oid = tc.relnamespace
+         WHERE c.contype = 'f'
+            AND c.conislocal
+            AND {} = $1::text::regclass
+         ORDER BY sn.nspname, sc.relname, c.conname",
+        side
+    );
+    let qualified = format!("{}.{}", quote_ident(schema), quote_ident(table));
+    let rows = client
+        .query(&sql, &[&qualified])
+        .await
+        .map_err(|e| format!("FK listesi alınamadı: {}", format_db_error(&e)))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForeignKeyLink {
+            constraint_name: row.get(0),
+            schema: row.get(1),
+            table: row.get(2),
+            columns: row.get(3),
+            referenced_schema: row.get(4),
+            referenced_table: row.get(5),
+            referenced_columns: row.get(6),
+            on_update: action_name(row.get(7)),
+            on_delete: action_name(row.get(8)),
+        })
+        .collect())
+}
+
+pub async fn list_table_references(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+) -> Result<TableReferences, String> {
+    Ok(TableReferences {
+        outgoing: foreign_key_links(client, schema, table, false).await?,
+        incoming: foreign_key_links(client, schema, table, true).await?,
+    })
+}
+
+/// Equality filters on `columns`. None when a value is NULL: with the default
+/// MATCH SIMPLE such a key references nothing.
+fn key_filters(columns: &[String], values: &[Option<String>]) -> Option<FilterGroup> {
+    if values.iter().any(Option::is_none) {
+        return None;
+    }
+    Some(FilterGroup {
+        logic: FilterLogic::And,
+        filters: columns
+            .iter()
+            .zip(values)
+            .map(|(column, value)| {
+                FilterNode::Condition(FilterCondition {
+                    column: column.clone(),
+                    operator: FilterOperator::Eq,
+                    value: value.clone(),
+                    values: Vec::new(),
+                    path: Vec::new(),
+                })
+            })
+            .collect(),
+    })
+}
+
+async fn filtered_rows(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    filters: &FilterGroup,
+    page: i64,
+    page_size: i64,
+) -> Result<TableDataResult, String> {
+    let options = TableDataOptions {
+        page,
+        page_size,
+        filters: Some(filters.clone()),
+        count_mode: Some(CountMode::Exact),
+        with_row_ids: true,
+        ..Default::default()
+    };
+    queries::query_table_data(client, schema, table, options).await
+}
+
+/// `values` are the row's values for the constraint's columns, in key order.
+pub async fn get_referenced_row(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    constraint_name: &str,
+    values: Vec<Option<String>>,
+) -> Result<ReferencedRow, String> {
+    let foreign_key = foreign_key_links(client, schema, table, false)
+        .await?
+        .into_iter()
+        .find(|fk| fk.constraint_name == constraint_name)
+        .ok_or_else(|| format!("Yabancı anahtar bulunamadı: {}", constraint_name))?;
+    if values.len() != foreign_key.columns.len() {
+        return Err("Değer sayısı yabancı anahtar kolon sayısıyla eşleşmiyor".to_string());
+    }
+
+    let filters = key_filters(&foreign_key.referenced_columns, &values);
+    let data = match &filters {
+        Some(filters) => Some(
+            filtered_rows(
+                client,
+                &foreign_key.referenced_schema,
+                &foreign_key.referenced_table,
+                filters,
+                1,
+                1,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    Ok(ReferencedRow {
+        filters: filters.unwrap_or(FilterGroup {
+            logic: FilterLogic::And,
+            filters: Vec::new(),
+        }),
+        foreign_key,
+        data,
+    })
+}
+
+/// Rows in other tables pointing to `row`, one page per constraint.
+pub async fn list_referencing_rows(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    row: &RowValues,
+    options: &ReferencingRowsOptions,
+) -> Result<Vec<ReferencingGroup>, String> {
+    if row.columns.len() != row.values.len() {
+        return Err("Kolon ve değer sayısı eşleşmiyor".to_string());
+    }
+    let page = options.page.unwrap_or(1).max(1);
+    let page_size = options
+        .page_size
+        .unwrap_or(DEFAULT_REFERENCING_PAGE_SIZE)
+        .clamp(1, 1000);
+
+    let mut groups = Vec::new();
+    for foreign_key in foreign_key_links(client, schema, table, true).await? {
+        if let Some(name) = &options.constraint_name {
+            if &foreign_key.constraint_name != name {
+                continue;
+            }
+        }
+
+        let mut values = Vec::with_capacity(foreign_key.referenced_columns.len());
+        for column in &foreign_key.referenced_columns {
+            let i = row
+                .columns
+                .iter()
+                .position(|c| c == column)
+                .ok_or_else(|| format!("Satırda {} kolonu eksik", column))?;
+            values.push(row.values[i].clone());
+        }
+
+        let filters = key_filters(&foreign_key.columns, &values);
+        let data = match &filters {
+            Some(filters) => Some(
+                filtered_rows(
+                    client,
+                    &foreign_key.schema,
+                    &foreign_key.table,
+                    filters,
+                    page,
+                    page_size,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+        groups.push(ReferencingGroup {
+            filters: filters.unwrap_or(FilterGroup {
+                logic: FilterLogic::And,
+                filters: Vec::new(),
+            }),
+            foreign_key,
+            data,
+        });
+    }
+
+    if let Some(name) = &options.constraint_name {
+        if groups.is_empty() {
+            return Err(format!("Yabancı anahtar bulunamadı: {}", name));
+        }
+    }
+    Ok(groups)
+}
@@ -0,0 +1,497 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident, quote_literal};
+
+// ── Roles ───────────────────────────────────────────────────────────────
+
+const MASKED_PASSWORD: &str = "'********'";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoleOptions {
+    pub name: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub superuser: bool,
+    #[serde(default)]
+    pub can_login: bool,
+    #[serde(default)]
+    pub can_create_db: bool,
+    #[serde(default)]
+    pub can_create_role: bool,
+    #[serde(default = "default_true")]
+    pub inherit: bool,
+    #[serde(default)]
+    pub replication: bool,
+    #[serde(default)]
+    pub bypass_rls: bool,
+    #[serde(default)]
+    pub connection_limit: Option<i32>,
+    /// Timestamp literal, e.g. "2025-12-31"
+    #[serde(default)]
+    pub valid_until: Option<String>,
+    #[serde(default)]
+    pub member_of: Vec<String>,
+}
+
+/// Only the fields that are set are changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlterRoleOptions {
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub superuser: Option<bool>,
+    #[serde(default)]
+    pub can_login: Option<bool>,
+    #[serde(default)]
+    pub can_create_db: Option<bool>,
+    #[serde(default)]
+    pub can_create_role: Option<bool>,
+    #[serde(default)]
+    pub inherit: Option<bool>,
+    #[serde(default)]
+    pub replication: Option<bool>,
+    #[serde(default)]
+    pub bypass_rls: Option<bool>,
+    #[serde(default)]
+    pub connection_limit: Option<i32>,
+    /// "infinity" removes the expiry
+    #[serde(default)]
+    pub valid_until: Option<String>,
+    #[serde(default)]
+    pub grant_membership: Vec<String>,
+    #[serde(default)]
+    pub revoke_membership: Vec<String>,
+    #[serde(default)]
+    pub rename_to: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn ident(name: &str, what: &str) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} adı boş olamaz", what));
+    }
+    Ok(quote_ident(name))
+}
+
+fn flag(enabled: bool, keyword: &str) -> String {
+    if enabled {
+        keyword.to_string()
+    } else {
+        format!("NO{}", keyword)
+    }
+}
+
+/// Statements to run and the same statements with passwords masked.
+struct RoleScript {
+    statements: Vec<String>,
+    display: Vec<String>,
+}
+
+impl RoleScript {
+    fn new() -> Self {
+        RoleScript {
+            statements: Vec::new(),
+            display: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, sql: String) {
+        self.display.push(sql.clone());
+        self.statements.push(sql);
+    }
+
+    fn push_masked(&mut self, sql: String, display: String) {
+        self.statements.push(sql);
+        self.display.push(display);
+    }
+
+    fn display_sql(&self) -> String {
+        self.display
+            .iter()
+            .map(|s| format!("{};", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn membership_statements(
+    script: &mut RoleScript,
+    role: &str,
+    grant: &[String],
+    revoke: &[String],
+) -> Result<(), String> {
+    for group in grant {
+        script.push(format!("GRANT {} TO {}", ident(group, "Rol")?, role));
+    }
+    for group in revoke {
+        script.push(format!("REVOKE {} FROM {}", ident(group, "Rol")?, role));
+    }
+    Ok(())
+}
+
+fn build_create_role(options: &CreateRoleOptions) -> Result<RoleScript, String> {
+    let role = ident(&options.name, "Rol")?;
+
+    let mut attrs = vec![
+        flag(options.superuser, "SUPERUSER"),
+        flag(options.can_login, "LOGIN"),
+        flag(options.can_create_db, "CREATEDB"),
+        flag(options.can_create_role, "CREATEROLE"),
+        flag(options.inherit, "INHERIT"),
+        flag(options.replication, "REPLICATION"),
+        flag(options.bypass_rls, "BYPASSRLS"),
+    ];
+    if let Some(limit) = options.connection_limit {
+        attrs.push(format!("CONNECTION LIMIT {}", limit));
+    }
+    if let Some(valid_until) = options.valid_until.as_deref().filter(|v| !v.trim().is_empty()) {
+        attrs.push(format!("VALID UNTIL {}", quote_literal(valid_until.trim())));
+    }
+
+    let base = format!("CREATE ROLE {} WITH {}", role, attrs.join(" "));
+    let mut script = RoleScript::new();
+    match &options.password {
+        Some(password) => script.push_masked(
+            format!("{} PASSWORD {}", base, quote_literal(password)),
+            format!("{} PASSWORD {}", base, MASKED_PASSWORD),
+        ),
+        None => script.push(base),
+    }
+    membership_statements(&mut script, &role, &options.member_of, &[])?;
+    Ok(script)
+}
+
+fn build_alter_role(name: &str, options: &AlterRoleOptions) -> Result<RoleScript, String> {
+    let role = ident(name, "Rol")?;
+
+    let mut attrs = Vec::new();
+    let flags = [
+        (options.superuser, "SUPERUSER"),
+        (options.can_login, "LOGIN"),
+        (options.can_create_db, "CREATEDB"),
+        (options.can_create_role, "CREATEROLE"),
+        (options.inherit, "INHERIT"),
+        (options.replication, "REPLICATION"),
+        (options.bypass_rls, "BYPASSRLS"),
+    ];
+    for (value, keyword) in flags {
+        if let Some(enabled) = value {
+            attrs.push(flag(enabled, keyword));
+        }
+    }
+    if let Some(limit) = options.connection_limit {
+        attrs.push(format!("CONNECTION LIMIT {}", limit));
+    }
+    if let Some(valid_until) = options.valid_until.as_deref().filter(|v| !v.trim().is_empty()) {
+        attrs.push(format!("VALID UNTIL {}", quote_literal(valid_until.trim())));
+    }
+
+    let mut script = RoleScript::new();
+    if !attrs.is_empty() || options.password.is_some() {
+        let base = format!("ALTER ROLE {} WITH {}", role, attrs.join(" "));
+        let base = base.trim_end().to_string();
+        match &options.password {
+            Some(password) => script.push_masked(
+                format!("{} PASSWORD {}", base, quote_literal(password)),
+                format!("{} PASSWORD {}", base, MASKED_PASSWORD),
+            ),
+            None => script.push(base),
+        }
+    }
+    membership_statements(
+        &mut script,
+        &role,
+        &options.grant_membership,
+        &options.revoke_membership,
+    )?;
+    if let Some(new_name) = &options.rename_to {
+        script.push(format!(
+            "ALTER ROLE {} RENAME TO {}",
+            role,
+            ident(new_name, "Yeni rol")?
+        ));
+    }
+
+    if script.statements.is_empty() {
+        return Err("Uygulanacak değişiklik yok".to_string());
+    }
+    Ok(script)
+}
+
+async fn run_script(client: &Arc<Client>, script: &RoleScript) -> Result<String, String> {
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
+
+    for sql in &script.statements {
+        if let Err(e) = client.execute(sql.as_str(), &[]).await {
+            client.execute("ROLLBACK", &[]).await.ok();
+            return Err(format_db_error(&e));
+        }
+    }
+
+    if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
+    }
+
+    Ok(script.display_sql())
+}
+
+/// Returns the SQL that ran, with passwords masked.
+pub async fn create_role(client: &Arc<Client>, options: &CreateRoleOptions) -> Result<String, String> {
+    let script = build_create_role(options)?;
+    run_script(client, &script).await
+}
+
+pub async fn alter_role(
+    client: &Arc<Client>,
+    name: &str,
+    options: &AlterRoleOptions,
+) -> Result<String, String> {
+    let script = build_alter_role(name, options)?;
+    run_script(client, &script).await
+}
+
+/// With `reassign_to`, objects owned by the role are handed over and its
+/// remaining privileges dropped first, so the role can be removed.
+/// `REASSIGN OWNED`/`DROP OWNED` only affect the current database.
+pub async fn drop_role(
+    client: &Arc<Client>,
+    name: &str,
+    reassign_to: Option<&str>,
+) -> Result<String, String> {
+    let role = ident(name, "Rol")?;
+
+    let mut script = RoleScript::new();
+    if let Some(new_owner) = reassign_to {
+        script.push(format!(
+            "REASSIGN OWNED BY {} TO {}",
+            role,
+            ident(new_owner, "Yeni sahip")?
+        ));
+        script.push(format!("DROP OWNED BY {}", role));
+    }
+    script.push(format!("DROP ROLE {}", role));
+    run_script(client, &script).await
+}
+
+// ── GRANT / REVOKE ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivilegeObjectType {
+    /// Tables, views, materialized views and foreign tables
+    Table,
+    Sequence,
+    Schema,
+    Function,
+    Procedure,
+    Database,
+    AllTablesInSchema,
+    AllSequencesInSchema,
+    AllFunctionsInSchema,
+}
+
+impl PrivilegeObjectType {
+    fn allowed_privileges(self) -> &'static [&'static str] {
+        match self {
+            PrivilegeObjectType::Table | PrivilegeObjectType::AllTablesInSchema => &[
+                "SELECT",
+                "INSERT",
+                "UPDATE",
+                "DELETE",
+                "TRUNCATE",
+                "REFERENCES",
+                "TRIGGER",
+                "MAINTAIN",
+            ],
+            PrivilegeObjectType::Sequence | PrivilegeObjectType::AllSequencesInSchema => {
+                &["USAGE", "SELECT", "UPDATE"]
+            }
+            PrivilegeObjectType::Schema => &["USAGE", "CREATE"],
+            PrivilegeObjectType::Function
+            | PrivilegeObjectType::Procedure
+            | PrivilegeObjectType::AllFunctionsInSchema => &["EXECUTE"],
+            PrivilegeObjectType::Database => &["CONNECT", "CREATE", "TEMPORARY", "TEMP"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivilegeChange {
+    pub object_type: PrivilegeObjectType,
+    /// Required for everything except schemas and databases
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Object name; not used for the "all_*_in_schema" types
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Identity arguments for functions and procedures, e.g. "integer, text"
+    #[serde(default)]
+    pub arguments: Option<String>,
+    /// e.g. ["SELECT", "UPDATE"] or ["ALL"]
+    pub privileges: Vec<String>,
+    /// Role names; "PUBLIC" grants to everyone
+    pub grantees: Vec<String>,
+    #[serde(default)]
+    pub with_grant_option: bool,
+    /// REVOKE only: also revoke from roles that were granted by the grantees
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+fn privilege_list(change: &PrivilegeChange) -> Result<String, String> {
+    if change.privileges.is_empty() {
+        return Err("En az bir yetki seçilmeli".to_string());
+    }
+    let allowed = change.object_type.allowed_privileges();
+    let mut privileges = Vec::new();
+    for privilege in &change.privileges {
+        let upper = privilege.trim().to_uppercase();
+        if upper == "ALL" || upper == "ALL PRIVILEGES" {
+            return Ok("ALL PRIVILEGES".to_string());
+        }
+        if !allowed.contains(&upper.as_str()) {
+            return Err(format!("Bu nesne türü için geçersiz yetki: {}", privilege));
+        }
+        if !privileges.contains(&upper) {
+            privileges.push(upper);
+        }
+    }
+    Ok(privileges.join(", "))
+}
+
+fn object_target(change: &PrivilegeChange) -> Result<String, String> {
+    let schema = || {
+        change
+            .schema
+            .as_deref()
+            .ok_or_else(|| "Şema belirtilmeli".to_string())
+            .and_then(|s| ident(s, "Şema"))
+    };
+    let name = || {
+        change
+            .name
+            .as_deref()
+            .ok_or_else(|| "Nesne adı belirtilmeli".to_string())
+            .and_then(|n| ident(n, "Nesne"))
+    };
+
+    Ok(match change.object_type {
+        PrivilegeObjectType::Table => format!("TABLE {}.{}", schema()?, name()?),
+        PrivilegeObjectType::Sequence => format!("SEQUENCE {}.{}", schema()?, name()?),
+        PrivilegeObjectType::Schema => {
+            let schema_name = change.schema.as_deref().or(change.name.as_deref());
+            let schema_name = schema_name.ok_or_else(|| "Şema belirtilmeli".to_string())?;
+            format!("SCHEMA {}", ident(schema_name, "Şema")?)
+        }
+        PrivilegeObjectType::Function | PrivilegeObjectType::Procedure => {
+            let keyword = if change.object_type == PrivilegeObjectType::Function {
+                "FUNCTION"
+            } else {
+                "PROCEDURE"
+            };
+            format!(
+                "{} {}.{}({})",
+                keyword,
+                schema()?,
+                name()?,
+                change.arguments.as_deref().unwrap_or("").trim()
+            )
+        }
+        PrivilegeObjectType::Database => format!("DATABASE {}", name()?),
+        PrivilegeObjectType::AllTablesInSchema => format!("ALL TABLES IN SCHEMA {}", schema()?),
+        PrivilegeObjectType::AllSequencesInSchema => {
+            format!("ALL SEQUENCES IN SCHEMA {}", schema()?)
+        }
+        PrivilegeObjectType::AllFunctionsInSchema => {
+            format!("ALL FUNCTIONS IN SCHEMA {}", schema()?)
+        }
+    })
+}
+
+fn grantee_list(grantees: &[String]) -> Result<String, String> {
+    if grantees.is_empty() {
+        return Err("En az bir rol seçilmeli".to_string());
+    }
+    let quoted = grantees
+        .iter()
+        .map(|g| {
+            if g.trim().eq_ignore_ascii_case("public") {
+                Ok("PUBLIC".to_string())
+            } else {
+                ident(g, "Rol")
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(quoted.join(", "))
+}
+
+pub fn build_grant_sql(change: &PrivilegeChange) -> Result<String, String> {
+    Ok(format!(
+        "GRANT {} ON {} TO {}{}",
+        privilege_list(change)?,
+        object_target(change)?,
+        grantee_list(&change.grantees)?,
+        if change.with_grant_option { " WITH GRANT OPTION" } else { "" }
+    ))
+}
+
+/// With `with_grant_option` only the grant option is revoked.
+pub fn build_revoke_sql(change: &PrivilegeChange) -> Result<String, String> {
+    Ok(format!(
+        "REVOKE {}{} ON {} FROM {}{}",
+        if change.with_grant_option { "GRANT OPTION FOR " } else { "" },
+        privilege_list(change)?,
+        object_target(change)?,
+        grantee_list(&change.grantees)?,
+        if change.cascade { " CASCADE" } else { "" }
+    ))
+}
+
+/// Replaces the argument list of a function or procedure with the identity
+/// arguments of the routine it resolves to, so only an existing signature
+/// reaches the statement.
+async fn resolve_routine(
+    client: &Arc<Client>,
+    change: &PrivilegeChange,
+) -> Result<PrivilegeChange, String> {
+    if !matches!(
+        change.object_type,
+        PrivilegeObjectType::Function | PrivilegeObjectType::Procedure
+    ) {
+        return Ok(change.clone());
+    }
+    let target = object_target(change)?;
+    let signature = target.split_once(' ').map_or(target.as_str(), |(_, s)| s);
+    let arguments: Option<String> = client
+        .query_one(
+            "SELECT pg_get_function_identity_arguments(to_regprocedure($1))",
+            &[&signature],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    let arguments = arguments.ok_or_else(|| format!("Fonksiyon bulunamadı: {}", signature))?;
+    Ok(PrivilegeChange {
+        arguments: Some(arguments),
+        ..change.clone()
+    })
+}
+
+pub async fn grant_privileges(client: &Arc<Client>, change: &PrivilegeChange) -> Result<String, String> {
+    let sql = build_grant_sql(&resolve_routine(client, change).await?)?;
+    client.execute(sql.as_str(), &[]).await.map_err(|e| format_db_error(&e))?;
+    Ok(format!("{};", sql))
+}
+
+pub async fn revoke_privileges(client: &Arc<Client>, change: &PrivilegeChange) -> Result<String, String> {
+    let sql = build_revoke_sql(&resolve_routine(client, change).await?)?;
+    client.execute(sql.as_str(), &[]).await.map_err(|e| format_db_error(&e))?;
+    Ok(format!("{};", sql))
+}
@@ -0,0 +1,1205 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{self, quote_ident, quote_literal, TypeInfo};
+
+// ── Schema comparison ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDiffItem {
+    /// "table", "column", "constraint", "index", "view", "function", "sequence" or "type"
+    pub object_type: String,
+    /// e.g. "users", "users.email"
+    pub name: String,
+    /// "added" (only in source), "removed" (only in target) or "changed"
+    pub change: String,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub sql: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDiffResult {
+    pub items: Vec<SchemaDiffItem>,
+    /// Statements that make the target schema match the source, in run order
+    pub script: String,
+}
+
+/// Order in which migration statements run: dependents are dropped first,
+/// objects are created before the objects that use them, drops come last.
+/// Functions are created before tables, as pg_dump does, so defaults,
+/// checks and generated columns can call them; their bodies aren't checked
+/// until they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    /// New enum labels can't be used in the transaction that adds them,
+    /// so these run before it
+    EnumValues,
+    DropViews,
+    DropForeignKeys,
+    DropConstraints,
+    DropIndexes,
+    Types,
+    Sequences,
+    Functions,
+    Tables,
+    Columns,
+    Constraints,
+    ForeignKeys,
+    Indexes,
+    Views,
+    DropColumns,
+    DropTables,
+    DropFunctions,
+    DropSequences,
+    DropTypes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnSnapshot {
+    name: String,
+    data_type: String,
+    not_null: bool,
+    default: Option<String>,
+    identity: String,
+    generated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ConstraintSnapshot {
+    kind: String,
+    definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IndexSnapshot {
+    unique: bool,
+    /// Definition from "USING ..." on, so it doesn't depend on the schema name
+    using: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TableSnapshot {
+    columns: Vec<ColumnSnapshot>,
+    constraints: BTreeMap<String, ConstraintSnapshot>,
+    indexes: BTreeMap<String, IndexSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FunctionSnapshot {
+    /// Quoted name and identity arguments, for DROP
+    signature: String,
+    is_procedure: bool,
+    result: String,
+    definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SequenceSnapshot {
+    data_type: String,
+    start: i64,
+    increment: i64,
+    min: i64,
+    max: i64,
+    cache: i64,
+    cycle: bool,
+}
+
+#[derive(Debug, Default)]
+struct SchemaSnapshot {
+    tables: BTreeMap<String, TableSnapshot>,
+    /// In creation (oid) order, so dependent views come after their bases
+    views: Vec<(String, String)>,
+    functions: BTreeMap<String, FunctionSnapshot>,
+    sequences: BTreeMap<String, SequenceSnapshot>,
+    types: BTreeMap<String, TypeInfo>,
+}
+
+fn snapshot_error(e: tokio_postgres::Error) -> String {
+    format!("Şema bilgisi alınamadı: {}", e)
+}
+
+async fn load_snapshot(client: &Arc<Client>, schema: &str) -> Result<SchemaSnapshot, String> {
+    let mut snapshot = SchemaSnapshot::default();
+
+    let rows = client
+        .query(
+            "SELECT c.relname, a.attname, format_type(a.atttypid, a.atttypmod), a.attnotnull,
+                    pg_get_expr(d.adbin, d.adrelid), a.attidentity::text, a.attgenerated = 's'
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             LEFT JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+             LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+             WHERE n.nspname = $1 AND c.relkind IN ('r', 'p')
+             ORDER BY c.relname, a.attnum",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    for row in &rows {
+        let table = snapshot.tables.entry(row.get(0)).or_default();
+        if let Some(name) = row.get::<_, Option<String>>(1) {
+            table.columns.push(ColumnSnapshot {
+                name,
+                data_type: row.get(2),
+                not_null: row.get(3),
+                default: row.get(4),
+                identity: row.get(5),
+                generated: row.get(6),
+            });
+        }
+    }
+
+    let rows = client
+        .query(
+            "SELECT c.relname, con.conname, con.contype::text, pg_get_constraintdef(con.oid, true)
+             FROM pg_constraint con
+             JOIN pg_class c ON c.oid = con.conrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1
+                AND c.relkind IN ('r', 'p')
+                AND con.contype IN ('p', 'u', 'c', 'f', 'x')",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    for row in &rows {
+        if let Some(table) = snapshot.tables.get_mut(&row.get::<_, String>(0)) {
+            table.constraints.insert(
+                row.get(1),
+                ConstraintSnapshot {
+                    kind: row.get(2),
+                    definition: row.get(3),
+                },
+            );
+        }
+    }
+
+    let rows = client
+        .query(
+            "SELECT t.relname, ic.relname, i.indisunique, pg_get_indexdef(i.indexrelid)
+             FROM pg_index i
+             JOIN pg_class ic ON ic.oid = i.indexrelid
+             JOIN pg_class t ON t.oid = i.indrelid
+             JOIN pg_namespace n ON n.oid = t.relnamespace
+             WHERE n.nspname = $1
+                AND t.relkind IN ('r', 'p')
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_constraint c
+                    WHERE c.conindid = i.indexrelid
+                        AND c.conrelid = i.indrelid
+                        AND c.contype IN ('p', 'u', 'x'))",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    for row in &rows {
+        let definition: String = row.get(3);
+        let using = definition
+            .find(" USING ")
+            .map(|pos| definition[pos + 1..].to_string())
+            .unwrap_or(definition);
+        if let Some(table) = snapshot.tables.get_mut(&row.get::<_, String>(0)) {
+            table.indexes.insert(
+                row.get(1),
+                IndexSnapshot {
+                    unique: row.get(2),
+                    using,
+                },
+            );
+        }
+    }
+
+    let rows = client
+        .query(
+            "SELECT c.relname, pg_get_viewdef(c.oid, true)
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relkind = 'v'
+             ORDER BY c.oid",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    snapshot.views = rows
+        .iter()
+        .map(|row| {
+            let definition: String = row.get(1);
+            (
+                row.get(0),
+                definition.trim().trim_end_matches(';').to_string(),
+            )
+        })
+        .collect();
+
+    // Extension members are left to the extension
+    let rows = client
+        .query(
+            "SELECT p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                    quote_ident(p.proname) || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                    p.prokind = 'p',
+                    COALESCE(pg_get_function_result(p.oid), ''),
+                    pg_get_functiondef(p.oid)
+             FROM pg_proc p
+             JOIN pg_namespace n ON n.oid = p.pronamespace
+             WHERE n.nspname = $1
+                AND p.prokind IN ('f', 'p')
+                AND NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e')",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    for row in &rows {
+        snapshot.functions.insert(
+            row.get(0),
+            FunctionSnapshot {
+                signature: row.get(1),
+                is_procedure: row.get(2),
+                result: row.get(3),
+                definition: row.get::<_, String>(4).trim_end().to_string(),
+            },
+        );
+    }
+
+    // Identity sequences belong to their column
+    let rows = client
+        .query(
+            "SELECT s.sequencename, s.data_type::text, s.start_value, s.increment_by,
+                    s.min_value, s.max_value, s.cache_size, s.cycle
+             FROM pg_sequences s
+             WHERE s.schemaname = $1
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_depend d
+                    WHERE d.objid = format('%I.%I', s.schemaname, s.sequencename)::regclass
+                        AND d.deptype = 'i')",
+            &[&schema],
+        )
+        .await
+        .map_err(snapshot_error)?;
+    for row in &rows {
+        snapshot.sequences.insert(
+            row.get(0),
+            SequenceSnapshot {
+                data_type: row.get(1),
+                start: row.get(2),
+                increment: row.get(3),
+                min: row.get(4),
+                max: row.get(5),
+                cache: row.get(6),
+                cycle: row.get(7),
+            },
+        );
+    }
+
+    for info in queries::list_types(client, schema).await? {
+        snapshot.types.insert(info.name.clone(), info);
+    }
+
+    Ok(snapshot)
+}
+
+// ── SQL rendering ───────────────────────────────────────────────────────
+
+fn column_definition(column: &ColumnSnapshot) -> String {
+    let mut sql = format!("{} {}", quote_ident(&column.name), column.data_type);
+    if !column.identity.is_empty() {
+        let kind = if column.identity == "a" {
+            "ALWAYS"
+        } else {
+            "BY DEFAULT"
+        };
+        sql.push_str(&format!(" GENERATED {} AS IDENTITY", kind));
+    } else if let Some(default) = &column.default {
+        if column.generated {
+            sql.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", default));
+        } else {
+            sql.push_str(&format!(" DEFAULT {}", default));
+        }
+    }
+    if column.not_null {
+        sql.push_str(" NOT NULL");
+    }
+    sql
+}
+
+fn sequence_options(seq: &SequenceSnapshot) -> String {
+    format!(
+        "AS {} INCREMENT BY {} MINVALUE {} MAXVALUE {} START WITH {} CACHE {}{}",
+        seq.data_type,
+        seq.increment,
+        seq.min,
+        seq.max,
+        seq.start,
+        seq.cache,
+        if seq.cycle { " CYCLE" } else { " NO CYCLE" }
+    )
+}
+
+fn type_definition(info: &TypeInfo, qualified: &str) -> String {
+    match info.kind.as_str() {
+        "enum" => {
+            let labels: Vec<String> = info.enum_values.iter().map(|v| quote_literal(v)).collect();
+            format!("CREATE TYPE {} AS ENUM ({});", qualified, labels.join(", "))
+        }
+        "composite" => format!(
+            "CREATE TYPE {} AS ({});",
+            qualified,
+            info.attributes.join(", ")
+        ),
+        "domain" => {
+            let mut sql = format!(
+                "CREATE DOMAIN {} AS {}",
+                qualified,
+                info.base_type.clone().unwrap_or_default()
+            );
+            if let Some(default) = &info.default_value {
+                sql.push_str(&format!(" DEFAULT {}", default));
+            }
+            for constraint in &info.constraints {
+                sql.push_str(&format!(" {}", constraint));
+            }
+            sql.push(';');
+            sql
+        }
+        _ => format!(
+            "CREATE TYPE {} AS RANGE (SUBTYPE = {});",
+            qualified,
+            info.base_type.clone().unwrap_or_default()
+        ),
+    }
+}
+
+/// Points a `pg_get_functiondef` header at another schema.
+fn retarget_function(definition: &str, source_schema: &str, target_schema: &str) -> String {
+    let from = format!(" {}.", quote_ident_min(source_schema));
+    let to = format!(" {}.", quote_ident_min(target_schema));
+    let header_end = definition.find('\n').unwrap_or(definition.len());
+    match definition[..header_end].find(&from) {
+        Some(pos) => format!(
+            "{}{}{}",
+            &definition[..pos],
+            to,
+            &definition[pos + from.len()..]
+        ),
+        None => definition.to_string(),
+    }
+}
+
+/// Quotes like the server's `quote_ident`: only when needed.
+fn quote_ident_min(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .map(|c| c.is_ascii_lowercase() || c == '_')
+        .unwrap_or(false)
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        quote_ident(name)
+    }
+}
+
+struct DiffBuilder {
+    items: Vec<SchemaDiffItem>,
+    statements: Vec<(Phase, String)>,
+    target: String,
+}
+
+impl DiffBuilder {
+    fn qualify(&self, name: &str) -> String {
+        format!("{}.{}", self.target, quote_ident(name))
+    }
+
+    fn push(
+        &mut self,
+        object_type: &str,
+        name: String,
+        change: &str,
+        source: Option<String>,
+        target: Option<String>,
+        sql: Vec<(Phase, String)>,
+    ) {
+        self.items.push(SchemaDiffItem {
+            object_type: object_type.to_string(),
+            name,
+            change: change.to_string(),
+            source,
+            target,
+            sql: sql.iter().map(|(_, s)| s.clone()).collect(),
+        });
+        self.statements.extend(sql);
+    }
+}
+
+fn diff_tables(b: &mut DiffBuilder, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    for (name, src) in &source.tables {
+        let table = b.qualify(name);
+        let tgt = match target.tables.get(name) {
+            Some(tgt) => tgt,
+            None => {
+                let columns: Vec<String> = src
+                    .columns
+                    .iter()
+                    .map(|c| format!("    {}", column_definition(c)))
+                    .collect();
+                let sql = format!("CREATE TABLE {} (\n{}\n);", table, columns.join(",\n"));
+                b.push(
+                    "table",
+                    name.clone(),
+                    "added",
+                    Some(sql.clone()),
+                    None,
+                    vec![(Phase::Tables, sql)],
+                );
+                diff_table_children(b, name, &TableSnapshot::default(), src);
+                continue;
+            }
+        };
+
+        for column in &src.columns {
+            let item_name = format!("{}.{}", name, column.name);
+            let col = quote_ident(&column.name);
+            match tgt.columns.iter().find(|c| c.name == column.name) {
+                None => {
+                    let def = column_definition(column);
+                    b.push(
+                        "column",
+                        item_name,
+                        "added",
+                        Some(def.clone()),
+                        None,
+                        vec![(
+                            Phase::Columns,
+                            format!("ALTER TABLE {} ADD COLUMN {};", table, def),
+                        )],
+                    );
+                }
+                Some(existing) if existing != column => {
+                    let alter = |action: String| {
+                        (
+                            Phase::Columns,
+                            format!("ALTER TABLE {} ALTER COLUMN {} {};", table, col, action),
+                        )
+                    };
+                    let mut sql = Vec::new();
+                    if existing.data_type != column.data_type {
+                        sql.push(alter(format!(
+                            "TYPE {} USING {}::{}",
+                            column.data_type, col, column.data_type
+                        )));
+                    }
+                    if existing.identity != column.identity {
+                        if !existing.identity.is_empty() {
+                            sql.push(alter("DROP IDENTITY".to_string()));
+                        }
+                        if !column.identity.is_empty() {
+                            let kind = if column.identity == "a" {
+                                "ALWAYS"
+                            } else {
+                                "BY DEFAULT"
+                            };
+                            sql.push(alter(format!("ADD GENERATED {} AS IDENTITY", kind)));
+                        }
+                    }
+                    if existing.default != column.default
+                        && !column.generated
+                        && column.identity.is_empty()
+                    {
+                        sql.push(alter(match &column.default {
+                            Some(default) => format!("SET DEFAULT {}", default),
+                            None => "DROP DEFAULT".to_string(),
+                        }));
+                    }
+                    if existing.not_null != column.not_null {
+                        sql.push(alter(
+                            if column.not_null {
+                                "SET NOT NULL"
+                            } else {
+                                "DROP NOT NULL"
+                            }
+                            .to_string(),
+                        ));
+                    }
+                    if existing.generated != column.generated {
+                        sql = vec![
+                            (
+                                Phase::Columns,
+                                format!("ALTER TABLE {} DROP COLUMN {};", table, col),
+                            ),
+                            (
+                                Phase::Columns,
+                                format!(
+                                    "ALTER TABLE {} ADD COLUMN {};",
+                                    table,
+                                    column_definition(column)
+                                ),
+                            ),
+                        ];
+                    }
+                    b.push(
+                        "column",
+                        item_name,
+                        "changed",
+                        Some(column_definition(column)),
+                        Some(column_definition(existing)),
+                        sql,
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for column in &tgt.columns {
+            if !src.columns.iter().any(|c| c.name == column.name) {
+                b.push(
+                    "column",
+                    format!("{}.{}", name, column.name),
+                    "removed",
+                    None,
+                    Some(column_definition(column)),
+                    vec![(
+                        Phase::DropColumns,
+                        format!(
+                            "ALTER TABLE {} DROP COLUMN {};",
+                            table,
+                            quote_ident(&column.name)
+                        ),
+                    )],
+                );
+            }
+        }
+
+        diff_table_children(b, name, tgt, src);
+    }
+
+    // Foreign keys of removed tables go first, so the tables can be dropped
+    // in any order. Kept tables can't keep a foreign key to a removed one:
+    // the source has no such table, so `diff_table_children` drops it.
+    for (name, tgt) in &target.tables {
+        if !source.tables.contains_key(name) {
+            let table = b.qualify(name);
+            let mut sql: Vec<(Phase, String)> = tgt
+                .constraints
+                .iter()
+                .filter(|(_, con)| con.kind == "f")
+                .map(|(con_name, _)| {
+                    (
+                        Phase::DropForeignKeys,
+                        format!(
+                            "ALTER TABLE {} DROP CONSTRAINT {};",
+                            table,
+                            quote_ident(con_name)
+                        ),
+                    )
+                })
+                .collect();
+            sql.push((Phase::DropTables, format!("DROP TABLE {};", table)));
+            b.push("table", name.clone(), "removed", None, Some(name.clone()), sql);
+        }
+    }
+}
+
+/// Constraints and indexes of a table that exists in the source.
+fn diff_table_children(b: &mut DiffBuilder, name: &str, tgt: &TableSnapshot, src: &TableSnapshot) {
+    let table = b.qualify(name);
+
+    let add_constraint = |con_name: &str, con: &ConstraintSnapshot| {
+        let phase = if con.kind == "f" {
+            Phase::ForeignKeys
+        } else {
+            Phase::Constraints
+        };
+        (
+            phase,
+            format!(
+                "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                table,
+                quote_ident(con_name),
+                con.definition
+            ),
+        )
+    };
+    let drop_constraint = |con_name: &str, con: &ConstraintSnapshot| {
+        let phase = if con.kind == "f" {
+            Phase::DropForeignKeys
+        } else {
+            Phase::DropConstraints
+        };
+        (
+            phase,
+            format!(
+                "ALTER TABLE {} DROP CONSTRAINT {};",
+                table,
+                quote_ident(con_name)
+            ),
+        )
+    };
+
+    for (con_name, con) in &src.constraints {
+        let item_name = format!("{}.{}", name, con_name);
+        match tgt.constraints.get(con_name) {
+            None => b.push(
+                "constraint",
+                item_name,
+                "added",
+                Some(con.definition.clone()),
+                None,
+                vec![add_constraint(con_name, con)],
+            ),
+            Some(existing) if existing != con => b.push(
+                "constraint",
+                item_name,
+                "changed",
+                Some(con.definition.clone()),
+                Some(existing.definition.clone()),
+                vec![
+                    drop_constraint(con_name, existing),
+                    add_constraint(con_name, con),
+                ],
+            ),
+            Some(_) => {}
+        }
+    }
+    for (con_name, con) in &tgt.constraints {
+        if !src.constraints.contains_key(con_name) {
+            b.push(
+                "constraint",
+                format!("{}.{}", name, con_name),
+                "removed",
+                None,
+                Some(con.definition.clone()),
+                vec![drop_constraint(con_name, con)],
+            );
+        }
+    }
+
+    let create_index = |idx_name: &str, idx: &IndexSnapshot| {
+        (
+            Phase::Indexes,
+            format!(
+                "CREATE {}INDEX {} ON {} {};",
+                if idx.unique { "UNIQUE " } else { "" },
+                quote_ident(idx_name),
+                table,
+                idx.using
+            ),
+        )
+    };
+    let drop_index = |idx_name: &str| {
+        (
+            Phase::DropIndexes,
+            format!("DROP INDEX {};", b.qualify(idx_name)),
+        )
+    };
+
+    let mut index_items = Vec::new();
+    for (idx_name, idx) in &src.indexes {
+        let item_name = format!("{}.{}", name, idx_name);
+        match tgt.indexes.get(idx_name) {
+            None => index_items.push((
+                item_name,
+                "added",
+                Some(idx.using.clone()),
+                None,
+                vec![create_index(idx_name, idx)],
+            )),
+            Some(existing) if existing != idx => index_items.push((
+                item_name,
+                "changed",
+                Some(idx.using.clone()),
+                Some(existing.using.clone()),
+                vec![drop_index(idx_name), create_index(idx_name, idx)],
+            )),
+            Some(_) => {}
+        }
+    }
+    for (idx_name, idx) in &tgt.indexes {
+        if !src.indexes.contains_key(idx_name) {
+            index_items.push((
+                format!("{}.{}", name, idx_name),
+                "removed",
+                None,
+                Some(idx.using.clone()),
+                vec![drop_index(idx_name)],
+            ));
+        }
+    }
+    for (item_name, change, source, target, sql) in index_items {
+        b.push("index", item_name, change, source, target, sql);
+    }
+}
+
+fn diff_views(b: &mut DiffBuilder, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    // Changed views are dropped and recreated since their columns may differ
+    for (name, definition) in target.views.iter().rev() {
+        let source_def = source.views.iter().find(|(n, _)| n == name).map(|(_, d)| d);
+        if source_def.is_none() {
+            let sql = format!("DROP VIEW {};", b.qualify(name));
+            b.push(
+                "view",
+                name.clone(),
+                "removed",
+                None,
+                Some(definition.clone()),
+                vec![(Phase::DropViews, sql)],
+            );
+        }
+    }
+    for (name, definition) in &source.views {
+        let create = (
+            Phase::Views,
+            format!("CREATE VIEW {} AS\n{};", b.qualify(name), definition),
+        );
+        match target.views.iter().find(|(n, _)| n == name).map(|(_, d)| d) {
+            None => b.push(
+                "view",
+                name.clone(),
+                "added",
+                Some(definition.clone()),
+                None,
+                vec![create],
+            ),
+            Some(existing) if existing != definition => {
+                let drop = (Phase::DropViews, format!("DROP VIEW {};", b.qualify(name)));
+                b.push(
+                    "view",
+                    name.clone(),
+                    "changed",
+                    Some(definition.clone()),
+                    Some(existing.clone()),
+                    vec![drop, create],
+                );
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn diff_functions(
+    b: &mut DiffBuilder,
+    source: &SchemaSnapshot,
+    target: &SchemaSnapshot,
+    source_schema: &str,
+    target_schema: &str,
+) {
+    let drop_sql = |f: &FunctionSnapshot, b: &DiffBuilder| {
+        format!(
+            "DROP {} {}.{};",
+            if f.is_procedure {
+                "PROCEDURE"
+            } else {
+                "FUNCTION"
+            },
+            b.target,
+            f.signature
+        )
+    };
+
+    for (key, src) in &source.functions {
+        let definition = retarget_function(&src.definition, source_schema, target_schema);
+        let create = (Phase::Functions, format!("{};", definition));
+        match target.functions.get(key) {
+            None => b.push(
+                "function",
+                key.clone(),
+                "added",
+                Some(definition.clone()),
+                None,
+                vec![create],
+            ),
+            Some(existing) if existing.definition != definition => {
+                // CREATE OR REPLACE can't change the result type or the kind
+                let mut sql = Vec::new();
+                if existing.result != src.result || existing.is_procedure != src.is_procedure {
+                    sql.push((Phase::Functions, drop_sql(existing, b)));
+                }
+                sql.push(create);
+                b.push(
+                    "function",
+                    key.clone(),
+                    "changed",
+                    Some(definition.clone()),
+                    Some(existing.definition.clone()),
+                    sql,
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, tgt) in &target.functions {
+        if !source.functions.contains_key(key) {
+            let sql = drop_sql(tgt, b);
+            b.push(
+                "function",
+                key.clone(),
+                "removed",
+                None,
+                Some(tgt.definition.clone()),
+                vec![(Phase::DropFunctions, sql)],
+            );
+        }
+    }
+}
+
+fn diff_sequences(b: &mut DiffBuilder, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    for (name, src) in &source.sequences {
+        let options = sequence_options(src);
+        match target.sequences.get(name) {
+            None => {
+                let sql = format!("CREATE SEQUENCE {} {};", b.qualify(name), options);
+                b.push(
+                    "sequence",
+                    name.clone(),
+                    "added",
+                    Some(options),
+                    None,
+                    vec![(Phase::Sequences, sql)],
+                );
+            }
+            Some(existing) if existing != src => {
+                // RESTART is left out on purpose: the target keeps its current value
+                let sql = format!("ALTER SEQUENCE {} {};", b.qualify(name), options);
+                b.push(
+                    "sequence",
+                    name.clone(),
+                    "changed",
+                    Some(options),
+                    Some(sequence_options(existing)),
+                    vec![(Phase::Sequences, sql)],
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, tgt) in &target.sequences {
+        if !source.sequences.contains_key(name) {
+            let sql = format!("DROP SEQUENCE {};", b.qualify(name));
+            b.push(
+                "sequence",
+                name.clone(),
+                "removed",
+                None,
+                Some(sequence_options(tgt)),
+                vec![(Phase::DropSequences, sql)],
+            );
+        }
+    }
+}
+
+fn diff_types(b: &mut DiffBuilder, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    for (name, src) in &source.types {
+        let qualified = b.qualify(name);
+        let definition = type_definition(src, &qualified);
+        match target.types.get(name) {
+            None => b.push(
+                "type",
+                name.clone(),
+                "added",
+                Some(definition.clone()),
+                None,
+                vec![(Phase::Types, definition)],
+            ),
+            Some(existing) => {
+                let existing_def = type_definition(existing, &qualified);
+                if existing_def == definition {
+                    continue;
+                }
+                let sql = match enum_additions(existing, src) {
+                    Some(additions) => additions
+                        .into_iter()
+                        .map(|(label, after)| {
+                            let position = match after {
+                                Some(after) => format!(" AFTER {}", quote_literal(&after)),
+                                None => {
+                                    format!(" BEFORE {}", quote_literal(&existing.enum_values[0]))
+                                }
+                            };
+                            (
+                                Phase::EnumValues,
+                                format!(
+                                    "ALTER TYPE {} ADD VALUE IF NOT EXISTS {}{};",
+                                    qualified,
+                                    quote_literal(&label),
+                                    position
+                                ),
+                            )
+                        })
+                        .collect(),
+                    // Other changes need the type recreated; this fails while it is in use
+                    None => vec![
+                        (Phase::Types, format!("DROP TYPE {};", qualified)),
+                        (Phase::Types, definition.clone()),
+                    ],
+                };
+                b.push(
+                    "type",
+                    name.clone(),
+                    "changed",
+                    Some(definition),
+                    Some(existing_def),
+                    sql,
+                );
+            }
+        }
+    }
+    for (name, tgt) in &target.types {
+        if !source.types.contains_key(name) {
+            let qualified = b.qualify(name);
+            let definition = type_definition(tgt, &qualified);
+            let keyword = if tgt.kind == "domain" {
+                "DOMAIN"
+            } else {
+                "TYPE"
+            };
+            b.push(
+                "type",
+                name.clone(),
+                "removed",
+                None,
+                Some(definition),
+                vec![(Phase::DropTypes, format!("DROP {} {};", keyword, qualified))],
+            );
+        }
+    }
+}
+
+/// Enum labels missing from the target, with the label each goes after
+/// (None = first), when the target labels keep their order in the source.
+fn enum_additions(target: &TypeInfo, source: &TypeInfo) -> Option<Vec<(String, Option<String>)>> {
+    if target.kind != "enum" || source.kind != "enum" || target.enum_values.is_empty() {
+        return None;
+    }
+    let kept: Vec<&String> = source
+        .enum_values
+        .iter()
+        .filter(|v| target.enum_values.contains(v))
+        .collect();
+    if kept.len() != target.enum_values.len()
+        || kept.iter().zip(&target.enum_values).any(|(a, b)| *a != b)
+    {
+        return None;
+    }
+    Some(
+        source
+            .enum_values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| !target.enum_values.contains(v))
+            .map(|(i, v)| {
+                (
+                    v.clone(),
+                    if i == 0 {
+                        None
+                    } else {
+                        Some(source.enum_values[i - 1].clone())
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Compares two schemas (possibly on different servers) and returns the
+/// differences plus a script that makes the target match the source.
+pub async fn compare_schemas(
+    source_client: &Arc<Client>,
+    source_schema: &str,
+    target_client: &Arc<Client>,
+    target_schema: &str,
+) -> Result<SchemaDiffResult, String> {
+    let (source, target) = tokio::try_join!(
+        load_snapshot(source_client, source_schema),
+        load_snapshot(target_client, target_schema)
+    )?;
+
+    let mut b = DiffBuilder {
+        items: Vec::new(),
+        statements: Vec::new(),
+        target: quote_ident(target_schema),
+    };
+    diff_types(&mut b, &source, &target);
+    diff_sequences(&mut b, &source, &target);
+    diff_tables(&mut b, &source, &target);
+    diff_functions(&mut b, &source, &target, source_schema, target_schema);
+    diff_views(&mut b, &source, &target);
+
+    Ok(SchemaDiffResult {
+        script: render_script(source_schema, target_schema, b.statements),
+        items: b.items,
+    })
+}
+
+/// Orders the statements by phase and wraps them in one transaction;
+/// enum labels are added before it.
+fn render_script(
+    source_schema: &str,
+    target_schema: &str,
+    mut statements: Vec<(Phase, String)>,
+) -> String {
+    if statements.is_empty() {
+        return String::new();
+    }
+    statements.sort_by_key(|(phase, _)| *phase);
+
+    let (before, body): (Vec<_>, Vec<_>) = statements
+        .into_iter()
+        .partition(|(phase, _)| *phase == Phase::EnumValues);
+
+    let mut script = format!("-- {} -> {}\n", source_schema, target_schema);
+    if !before.is_empty() {
+        let before: Vec<String> = before.into_iter().map(|(_, sql)| sql).collect();
+        script.push_str(&format!("{}\n\n", before.join("\n")));
+    }
+    if body.is_empty() {
+        return script;
+    }
+
+    let mut lines = Vec::new();
+    // Function bodies may use tables that are created later in the script
+    if body.iter().any(|(phase, _)| *phase == Phase::Functions) {
+        lines.push("SET LOCAL check_function_bodies = false;".to_string());
+    }
+    lines.extend(body.into_iter().map(|(_, sql)| sql));
+    script.push_str(&format!("BEGIN;\n\n{}\n\nCOMMIT;\n", lines.join("\n\n")));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statements(list: &[(Phase, &str)]) -> Vec<(Phase, String)> {
+        list.iter().map(|(p, s)| (*p, s.to_string())).collect()
+    }
+
+    #[test]
+    fn empty_diff_has_no_script() {
+        assert_eq!(render_script("a", "b", Vec::new()), "");
+    }
+
+    #[test]
+    fn functions_are_created_before_tables_that_use_them() {
+        let script = render_script(
+            "a",
+            "b",
+            statements(&[
+                (Phase::DropFunctions, "DROP FUNCTION old();"),
+                (Phase::Columns, "ALTER TABLE t ADD COLUMN c text DEFAULT f();"),
+                (Phase::Tables, "CREATE TABLE t ();"),
+                (Phase::Functions, "CREATE FUNCTION f() ...;"),
+            ]),
+        );
+        assert_eq!(
+            script,
+            "-- a -> b\nBEGIN;\n\nSET LOCAL check_function_bodies = false;\n\n\
+             CREATE FUNCTION f() ...;\n\nCREATE TABLE t ();\n\n\
+             ALTER TABLE t ADD COLUMN c text DEFAULT f();\n\nDROP FUNCTION old();\n\nCOMMIT;\n"
+        );
+    }
+
+    #[test]
+    fn enum_values_are_added_outside_the_transaction() {
+        let script = render_script(
+            "a",
+            "b",
+            statements(&[
+                (Phase::Columns, "ALTER TABLE t ADD COLUMN m mood DEFAULT 'new';"),
+                (Phase::EnumValues, "ALTER TYPE mood ADD VALUE IF NOT EXISTS 'new';"),
+            ]),
+        );
+        assert_eq!(
+            script,
+            "-- a -> b\nALTER TYPE mood ADD VALUE IF NOT EXISTS 'new';\n\n\
+             BEGIN;\n\nALTER TABLE t ADD COLUMN m mood DEFAULT 'new';\n\nCOMMIT;\n"
+        );
+
+        let only_enums = render_script(
+            "a",
+            "b",
+            statements(&[(Phase::EnumValues, "ALTER TYPE mood ADD VALUE IF NOT EXISTS 'x';")]),
+        );
+        assert!(!only_enums.contains("BEGIN"));
+    }
+
+    fn table(constraints: &[(&str, &str, &str)]) -> TableSnapshot {
+        TableSnapshot {
+            columns: Vec::new(),
+            constraints: constraints
+                .iter()
+                .map(|(name, kind, definition)| {
+                    (
+                        name.to_string(),
+                        ConstraintSnapshot {
+                            kind: kind.to_string(),
+                            definition: definition.to_string(),
+                        },
+                    )
+                })
+                .collect(),
+            indexes: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn foreign_keys_are_dropped_before_removed_tables() {
+        let mut source = SchemaSnapshot::default();
+        source.tables.insert("orders".to_string(), table(&[]));
+        let mut target = SchemaSnapshot::default();
+        target.tables.insert(
+            "orders".to_string(),
+            table(&[("orders_item_fkey", "f", "FOREIGN KEY (item) REFERENCES items(id)")]),
+        );
+        target.tables.insert(
+            "items".to_string(),
+            table(&[("items_tag_fkey", "f", "FOREIGN KEY (tag) REFERENCES tags(id)")]),
+        );
+        target.tables.insert("tags".to_string(), table(&[]));
+
+        let mut b = DiffBuilder {
+            items: Vec::new(),
+            statements: Vec::new(),
+            target: "\"public\"".to_string(),
+        };
+        diff_tables(&mut b, &source, &target);
+        let script = render_script("a", "b", b.statements);
+        assert_eq!(
+            script,
+            "-- a -> b\nBEGIN;\n\n\
+             ALTER TABLE \"public\".\"orders\" DROP CONSTRAINT \"orders_item_fkey\";\n\n\
+             ALTER TABLE \"public\".\"items\" DROP CONSTRAINT \"items_tag_fkey\";\n\n\
+             DROP TABLE \"public\".\"items\";\n\nDROP TABLE \"public\".\"tags\";\n\nCOMMIT;\n"
+        );
+    }
+
+    fn enum_type(values: &[&str]) -> TypeInfo {
+        TypeInfo {
+            name: "mood".to_string(),
+            schema: "public".to_string(),
+            kind: "enum".to_string(),
+            base_type: None,
+            enum_values: values.iter().map(|v| v.to_string()).collect(),
+            attributes: Vec::new(),
+            constraints: Vec::new(),
+            default_value: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn enum_additions_keep_label_positions() {
+        assert_eq!(
+            enum_additions(&enum_type(&["b", "d"]), &enum_type(&["a", "b", "c", "d"])),
+            Some(vec![
+                ("a".to_string(), None),
+                ("c".to_string(), Some("b".to_string())),
+            ])
+        );
+        // Reordered or removed labels can't be expressed with ADD VALUE
+        assert_eq!(enum_additions(&enum_type(&["b", "a"]), &enum_type(&["a", "b"])), None);
+        assert_eq!(enum_additions(&enum_type(&["a", "x"]), &enum_type(&["a"])), None);
+    }
+}
@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident, quote_literal};
+
+// ── Server configuration (pg_settings) ──────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerSetting {
+    pub name: String,
+    pub value: String,
+    pub unit: Option<String>,
+    pub description: Option<String>,
+    pub extra_description: Option<String>,
+    /// "bool", "integer", "real", "string" or "enum"
+    pub value_type: String,
+    /// "postmaster" needs a restart, "sighup" a reload, "user"/"superuser" apply per session
+    pub context: String,
+    /// Where the current value comes from: "default", "configuration file", "database", ...
+    pub source: String,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub enum_values: Option<Vec<String>>,
+    pub default_value: Option<String>,
+    pub reset_value: Option<String>,
+    pub pending_restart: bool,
+    pub requires_restart: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingCategory {
+    pub name: String,
+    pub settings: Vec<ServerSetting>,
+}
+
+pub async fn list_settings(client: &Arc<Client>) -> Result<Vec<SettingCategory>, String> {
+    let rows = client
+        .query(
+            "SELECT name, setting, unit, short_desc, extra_desc, vartype, context, source,
+                    min_val, max_val, enumvals, boot_val, reset_val, pending_restart,
+                    COALESCE(category, '')
+             FROM pg_settings
+             ORDER BY category, name",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Sunucu ayarları alınamadı: {}", e))?;
+
+    let mut categories: Vec<SettingCategory> = Vec::new();
+    for row in &rows {
+        let context: String = row.get(6);
+        let setting = ServerSetting {
+            name: row.get(0),
+            value: row.get(1),
+            unit: row.get(2),
+            description: row.get(3),
+            extra_description: row.get(4),
+            value_type: row.get(5),
+            requires_restart: context == "postmaster",
+            context,
+            source: row.get(7),
+            min_value: row.get(8),
+            max_value: row.get(9),
+            enum_values: row.get(10),
+            default_value: row.get(11),
+            reset_value: row.get(12),
+            pending_restart: row.get(13),
+        };
+        let category: String = row.get(14);
+        match categories.last_mut() {
+            Some(last) if last.name == category => last.settings.push(setting),
+            _ => categories.push(SettingCategory {
+                name: category,
+                settings: vec![setting],
+            }),
+        }
+    }
+    Ok(categories)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SettingScope {
+    /// `ALTER SYSTEM`, written to postgresql.auto.conf
+    System,
+    Database {
+        name: String,
+    },
+    /// With `database`, only applies when the role connects to that database
+    Role {
+        name: String,
+        #[serde(default)]
+        database: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingChange {
+    pub name: String,
+    /// None resets the parameter for the scope
+    #[serde(default)]
+    pub value: Option<String>,
+    pub scope: SettingScope,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingChangeResult {
+    pub sql: String,
+    pub reloaded: bool,
+    /// The new value only takes effect after a server restart
+    pub pending_restart: bool,
+    /// Database and role settings apply to new sessions only
+    pub new_sessions_only: bool,
+}
+
+/// Known parameters are accepted as is; custom ones need an extension prefix
+/// ("auto_explain.log_min_duration").
+async fn validate_setting_name(client: &Arc<Client>, name: &str) -> Result<Option<String>, String> {
+    let valid_chars = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid_chars {
+        return Err(format!("Geçersiz parametre adı: {}", name));
+    }
+
+    let row = client
+        .query_opt("SELECT name, context FROM pg_settings WHERE name = lower($1)", &[&name])
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    match row {
+        Some(row) => {
+            let context: String = row.get(1);
+            if context == "internal" {
+                return Err(format!("{} parametresi değiştirilemez", name));
+            }
+            Ok(Some(context))
+        }
+        None if name.contains('.') => Ok(None),
+        None => Err(format!("Bilinmeyen parametre: {}", name)),
+    }
+}
+
+/// Parameters whose list elements are quoted one by one, so a comma inside
+/// a single literal would end up in one element's name.
+const LIST_PARAMETERS: &[&str] = &[
+    "local_preload_libraries",
+    "search_path",
+    "session_preload_libraries",
+    "shared_preload_libraries",
+    "temp_tablespaces",
+    "unix_socket_directories",
+];
+
+/// Splits `a, "b,c"` into `["a", "b,c"]`; double quotes keep commas and
+/// spaces inside an element.
+fn split_list_value(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut chars = value.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut item = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == '"' {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                item.push(c);
+            }
+            while chars.peek().is_some_and(|c| *c != ',') {
+                chars.next();
+            }
+        } else {
+            while let Some(c) = chars.peek().filter(|c| **c != ',') {
+                item.push(*c);
+                chars.next();
+            }
+            item = item.trim_end().to_string();
+        }
+        if !item.is_empty() {
+            items.push(item);
+        }
+        if chars.next().is_none() {
+            break;
+        }
+    }
+    items
+}
+
+fn setting_value_sql(name: &str, value: &str) -> String {
+    if !LIST_PARAMETERS.contains(&name.to_lowercase().as_str()) {
+        return quote_literal(value);
+    }
+    let items = split_list_value(value);
+    if items.is_empty() {
+        return "''".to_string();
+    }
+    items
+        .iter()
+        .map(|item| quote_literal(item))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn build_setting_sql(change: &SettingChange) -> String {
+    let target = match &change.scope {
+        SettingScope::System => "ALTER SYSTEM".to_string(),
+        SettingScope::Database { name } => format!("ALTER DATABASE {}", quote_ident(name)),
+        SettingScope::Role { name, database } => match database {
+            Some(db) => format!(
+                "ALTER ROLE {} IN DATABASE {}",
+                quote_ident(name),
+                quote_ident(db)
+            ),
+            None => format!("ALTER ROLE {}", quote_ident(name)),
+        },
+    };
+    match &change.value {
+        Some(value) => format!(
+            "{} SET {} = {}",
+            target,
+            change.name,
+            setting_value_sql(&change.name, value)
+        ),
+        None => format!("{} RESET {}", target, change.name),
+    }
+}
+
+/// Applies the change. `ALTER SYSTEM` changes are followed by a
+/// configuration reload so sighup-level parameters take effect right away;
+/// `reloaded` is false when that reload isn't permitted.
+pub async fn apply_setting(
+    client: &Arc<Client>,
+    change: &SettingChange,
+) -> Result<SettingChangeResult, String> {
+    let context = validate_setting_name(client, &change.name).await?;
+    let sql = build_setting_sql(change);
+
+    // ALTER SYSTEM can't run inside a transaction block
+    client
+        .batch_execute(&sql)
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    let reloaded = match change.scope {
+        SettingScope::System => client
+            .query_one("SELECT pg_reload_conf()", &[])
+            .await
+            .map(|row| row.get(0))
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    // pending_restart is only updated once the configuration file is re-read,
+    // which may not have happened yet
+    let pending_restart = match change.scope {
+        SettingScope::System if context.as_deref() == Some("postmaster") => true,
+        SettingScope::System => client
+            .query_opt(
+                "SELECT pending_restart FROM pg_settings WHERE name = lower($1)",
+                &[&change.name],
+            )
+            .await
+            .map_err(|e| format_db_error(&e))?
+            .map(|row| row.get(0))
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    Ok(SettingChangeResult {
+        sql,
+        reloaded,
+        pending_restart,
+        new_sessions_only: !matches!(change.scope, SettingScope::System),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(name: &str, value: Option<&str>, scope: SettingScope) -> SettingChange {
+        SettingChange {
+            name: name.to_string(),
+            value: value.map(|v| v.to_string()),
+            scope,
+        }
+    }
+
+    #[test]
+    fn system_set_and_reset() {
+        assert_eq!(
+            build_setting_sql(&change("work_mem", Some("64MB"), SettingScope::System)),
+            "ALTER SYSTEM SET work_mem = '64MB'"
+        );
+        assert_eq!(
+            build_setting_sql(&change("work_mem", None, SettingScope::System)),
+            "ALTER SYSTEM RESET work_mem"
+        );
+    }
+
+    #[test]
+    fn database_and_role_targets() {
+        let db = SettingScope::Database {
+            name: "app".to_string(),
+        };
+        assert_eq!(
+            build_setting_sql(&change("statement_timeout", Some("5s"), db)),
+            "ALTER DATABASE \"app\" SET statement_timeout = '5s'"
+        );
+        let role = SettingScope::Role {
+            name: "reader".to_string(),
+            database: Some("app".to_string()),
+        };
+        assert_eq!(
+            build_setting_sql(&change("work_mem", None, role)),
+            "ALTER ROLE \"reader\" IN DATABASE \"app\" RESET work_mem"
+        );
+    }
+
+    #[test]
+    fn scalar_values_are_one_literal() {
+        assert_eq!(
+            build_setting_sql(&change("application_name", Some("a, b's"), SettingScope::System)),
+            "ALTER SYSTEM SET application_name = 'a, b''s'"
+        );
+    }
+
+    #[test]
+    fn list_values_are_split() {
+        assert_eq!(
+            build_setting_sql(&change(
+                "shared_preload_libraries",
+                Some("pg_stat_statements, auto_explain"),
+                SettingScope::System
+            )),
+            "ALTER SYSTEM SET shared_preload_libraries = 'pg_stat_statements', 'auto_explain'"
+        );
+        assert_eq!(
+            build_setting_sql(&change(
+                "search_path",
+                Some("\"$user\", public,\"a,b\""),
+                SettingScope::System
+            )),
+            "ALTER SYSTEM SET search_path = '$user', 'public', 'a,b'"
+        );
+        assert_eq!(
+            build_setting_sql(&change("temp_tablespaces", Some(""), SettingScope::System)),
+            "ALTER SYSTEM SET temp_tablespaces = ''"
+        );
+    }
+}
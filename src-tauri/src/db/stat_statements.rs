@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::queries::{format_db_error, quote_ident};
+
+// ── pg_stat_statements ──────────────────────────────────────────────────
+
+pub const DEFAULT_STATEMENT_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatStatementsStatus {
+    /// `CREATE EXTENSION` has been run in this database
+    pub installed: bool,
+    /// The extension can be created on this server
+    pub available: bool,
+    /// Listed in `shared_preload_libraries`; without it the view can't be read
+    pub preloaded: bool,
+    pub version: Option<String>,
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementOrder {
+    #[default]
+    TotalTime,
+    MeanTime,
+    Calls,
+    Rows,
+    SharedBlocksRead,
+    TempBlocks,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatStatementsOptions {
+    #[serde(default)]
+    pub order_by: StatementOrder,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub user_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementStats {
+    /// A 64-bit hash, sent as a string since it doesn't fit a JavaScript number
+    pub query_id: Option<String>,
+    pub database: Option<String>,
+    pub user_name: Option<String>,
+    /// Normalized text with `$n` placeholders
+    pub query: String,
+    /// Highest `$n` in `query`; values for these are needed to run EXPLAIN
+    pub parameter_count: usize,
+    pub calls: i64,
+    pub total_time_ms: f64,
+    pub mean_time_ms: f64,
+    pub min_time_ms: f64,
+    pub max_time_ms: f64,
+    pub rows: i64,
+    pub shared_blocks_hit: i64,
+    pub shared_blocks_read: i64,
+    pub temp_blocks_read: i64,
+    pub temp_blocks_written: i64,
+    /// Share of the total time of the listed database/user
+    pub percent_of_total: f64,
+}
+
+pub async fn get_stat_statements_status(client: &Arc<Client>) -> Result<StatStatementsStatus, String> {
+    let row = client
+        .query_one(
+            "SELECT e.extversion, n.nspname::text,
+                    EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'pg_stat_statements'),
+                    current_setting('shared_preload_libraries') ~ '(^|[\\s,])pg_stat_statements($|[\\s,])'
+             FROM (SELECT 1) dummy
+             LEFT JOIN pg_extension e ON e.extname = 'pg_stat_statements'
+             LEFT JOIN pg_namespace n ON n.oid = e.extnamespace",
+            &[],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    let version: Option<String> = row.get(0);
+    Ok(StatStatementsStatus {
+        installed: version.is_some(),
+        available: row.get(2),
+        preloaded: row.get(3),
+        version,
+        schema: row.get(1),
+    })
+}
+
+/// Qualified view name, or an error explaining why the view can't be used.
+async fn stat_statements_view(client: &Arc<Client>) -> Result<String, String> {
+    let status = get_stat_statements_status(client).await?;
+    match status.schema {
+        Some(schema) if status.installed => {
+            if !status.preloaded {
+                return Err(
+                    "pg_stat_statements shared_preload_libraries ayarında yüklü değil".to_string(),
+                );
+            }
+            Ok(format!("{}.pg_stat_statements", quote_ident(&schema)))
+        }
+        _ => Err("pg_stat_statements eklentisi bu veritabanında kurulu değil".to_string()),
+    }
+}
+
+/// `$n` placeholders in a normalized statement, ignoring string literals.
+fn parameter_count(query: &str) -> usize {
+    let mut max = 0;
+    let mut in_string = false;
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => in_string = !in_string,
+            '$' if !in_string => {
+                let mut digits = String::new();
+                while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(*d);
+                    chars.next();
+                }
+                if let Ok(n) = digits.parse::<usize>() {
+                    max = max.max(n);
+                }
+            }
+            _ => {}
+        }
+    }
+    max
+}
+
+pub async fn get_top_statements(
+    client: &Arc<Client>,
+    options: &StatStatementsOptions,
+) -> Result<Vec<StatementStats>, String> {
+    let view = stat_statements_view(client).await?;
+
+    // PG13 (extension 1.8) renamed *_time to *_exec_time
+    let has_exec_time: bool = client
+        .query_one(
+            "SELECT EXISTS (
+                SELECT 1 FROM pg_attribute
+                WHERE attrelid = $1::text::regclass AND attname = 'total_exec_time'
+             )",
+            &[&view],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?
+        .get(0);
+    let time = |name: &str| {
+        if has_exec_time {
+            format!("s.{}_exec_time", name)
+        } else {
+            format!("s.{}_time", name)
+        }
+    };
+
+    let order = match options.order_by {
+        StatementOrder::TotalTime => "total_time",
+        StatementOrder::MeanTime => "mean_time",
+        StatementOrder::Calls => "calls",
+        StatementOrder::Rows => "rows",
+        StatementOrder::SharedBlocksRead => "shared_blks_read",
+        StatementOrder::TempBlocks => "temp_blks_read + temp_blks_written",
+    };
+    let limit = options.limit.unwrap_or(DEFAULT_STATEMENT_LIMIT).clamp(1, 1000);
+
+    let sql = format!(
+        "SELECT * FROM (
+            SELECT s.queryid::text, d.datname::text, r.rolname::text, COALESCE(s.query, '') as query,
+                   s.calls, {total}::float8 as total_time, {mean}::float8 as mean_time,
+                   {min}::float8 as min_time, {max}::float8 as max_time,
+                   s.rows, s.shared_blks_hit, s.shared_blks_read,
+                   s.temp_blks_read, s.temp_blks_written,
+                   COALESCE(100.0 * {total} / NULLIF(SUM({total}) OVER (), 0), 0)::float8
+            FROM {view} s
+            LEFT JOIN pg_database d ON d.oid = s.dbid
+            LEFT JOIN pg_roles r ON r.oid = s.userid
+            WHERE ($1::text IS NULL OR d.datname = $1)
+               AND ($2::text IS NULL OR r.rolname = $2)
+         ) t
+         ORDER BY {order} DESC
+         LIMIT $3",
+        total = time("total"),
+        mean = time("mean"),
+        min = time("min"),
+        max = time("max"),
+        view = view,
+        order = order,
+    );
+
+    let rows = client
+        .query(&sql, &[&options.database, &options.user_name, &limit])
+        .await
+        .map_err(|e| format!("pg_stat_statements okunamadı: {}", format_db_error(&e)))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let query: String = row.get(3);
+            StatementStats {
+                query_id: row.get(0),
+                database: row.get(1),
+                user_name: row.get(2),
+                parameter_count: parameter_count(&query),
+                query,
+                calls: row.get(4),
+                total_time_ms: row.get(5),
+                mean_time_ms: row.get(6),
+                min_time_ms: row.get(7),
+                max_time_ms: row.get(8),
+                rows: row.get(9),
+                shared_blocks_hit: row.get(10),
+                shared_blocks_read: row.get(11),
+                temp_blocks_read: row.get(12),
+                temp_blocks_written: row.get(13),
+                percent_of_total: row.get(14),
+            }
+        })
+        .collect())
+}
+
+pub async fn reset_stat_statements(client: &Arc<Client>) -> Result<(), String> {
+    let view = stat_statements_view(client).await?;
+    let schema = view.trim_end_matches(".pg_stat_statements");
+    client
+        .execute(&format!("SELECT {}.pg_stat_statements_reset()", schema), &[])
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_count_takes_highest_placeholder() {
+        assert_eq!(parameter_count("SELECT * FROM t WHERE a = $1 AND b = $2"), 2);
+        assert_eq!(parameter_count("SELECT $3, $1"), 3);
+        assert_eq!(parameter_count("SELECT $10"), 10);
+        assert_eq!(parameter_count("SELECT 1"), 0);
+    }
+
+    #[test]
+    fn parameter_count_ignores_string_literals() {
+        assert_eq!(parameter_count("SELECT '$5' || $1"), 1);
+        assert_eq!(parameter_count("SELECT 'it''s $4', $2"), 2);
+    }
+}
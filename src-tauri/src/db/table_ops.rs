@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+use super::databases::{dependent_objects, DependentObject};
+use super::queries::{format_db_error, quote_ident, quote_literal};
+
+// ── Table operations ────────────────────────────────────────────────────
+
+fn qualified(schema: &str, table: &str) -> Result<String, String> {
+    if schema.trim().is_empty() || table.trim().is_empty() {
+        return Err("Şema ve tablo adı boş olamaz".to_string());
+    }
+    Ok(format!("{}.{}", quote_ident(schema), quote_ident(table)))
+}
+
+async fn run(client: &Arc<Client>, sql: String) -> Result<String, String> {
+    client
+        .batch_execute(&sql)
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    Ok(sql)
+}
+
+pub async fn truncate_table(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    restart_identity: bool,
+    cascade: bool,
+) -> Result<String, String> {
+    let sql = format!(
+        "TRUNCATE TABLE {}{}{}",
+        qualified(schema, table)?,
+        if restart_identity { " RESTART IDENTITY" } else { "" },
+        if cascade { " CASCADE" } else { "" }
+    );
+    run(client, sql).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDropPreview {
+    pub schema: String,
+    pub table: String,
+    /// Objects dropped along with the table, including its own indexes and constraints
+    pub objects: Vec<DependentObject>,
+    /// Other objects (views, foreign keys, ...) depend on the table
+    pub requires_cascade: bool,
+    pub sql: String,
+}
+
+pub async fn preview_drop_table(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+) -> Result<TableDropPreview, String> {
+    let target = qualified(schema, table)?;
+    let row = client
+        .query_one(
+            "SELECT $1::text::regclass::oid,
+                    EXISTS (
+                        SELECT 1 FROM pg_depend d
+                        WHERE d.refclassid = 'pg_class'::regclass
+                            AND d.refobjid = $1::text::regclass
+                            AND d.deptype = 'n'
+                            AND NOT (d.classid = 'pg_rewrite'::regclass AND EXISTS (
+                                SELECT 1 FROM pg_rewrite r
+                                WHERE r.oid = d.objid AND r.ev_class = $1::text::regclass))
+                            AND NOT (d.classid = 'pg_constraint'::regclass AND EXISTS (
+                                SELECT 1 FROM pg_constraint c
+                                WHERE c.oid = d.objid AND c.conrelid = $1::text::regclass))
+                    )",
+            &[&target],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?;
+    let oid: u32 = row.get(0);
+    let requires_cascade: bool = row.get(1);
+
+    let objects = dependent_objects(client, "pg_class", oid, schema).await?;
+    Ok(TableDropPreview {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        sql: format!(
+            "DROP TABLE {}{}",
+            target,
+            if requires_cascade { " CASCADE" } else { "" }
+        ),
+        objects,
+        requires_cascade,
+    })
+}
+
+pub async fn drop_table(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    cascade: bool,
+) -> Result<String, String> {
+    let sql = format!(
+        "DROP TABLE {}{}",
+        qualified(schema, table)?,
+        if cascade { " CASCADE" } else { "" }
+    );
+    run(client, sql).await
+}
+
+pub async fn rename_table(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    new_name: &str,
+) -> Result<String, String> {
+    if new_name.trim().is_empty() {
+        return Err("Yeni tablo adı boş olamaz".to_string());
+    }
+    let sql = format!(
+        "ALTER TABLE {} RENAME TO {}",
+        qualified(schema, table)?,
+        quote_ident(new_name)
+    );
+    run(client, sql).await
+}
+
+/// Owned sequences and indexes move with the table.
+pub async fn set_table_schema(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    new_schema: &str,
+) -> Result<String, String> {
+    if new_schema.trim().is_empty() {
+        return Err("Hedef şema adı boş olamaz".to_string());
+    }
+    let sql = format!(
+        "ALTER TABLE {} SET SCHEMA {}",
+        qualified(schema, table)?,
+        quote_ident(new_schema)
+    );
+    run(client, sql).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateTableOptions {
+    pub new_name: String,
+    /// Defaults to the source table's schema
+    #[serde(default)]
+    pub target_schema: Option<String>,
+    #[serde(default)]
+    pub include_data: bool,
+    #[serde(default = "default_true")]
+    pub include_indexes: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Copies columns, defaults, constraints, comments and (optionally) indexes
+/// with `CREATE TABLE ... (LIKE ...)`, then the rows if requested. Identity
+/// columns get their own sequences from `LIKE`; serial columns would keep
+/// drawing from the source's sequence, so they get a new sequence owned by
+/// the copy. With data, each of these sequences continues after the copied
+/// values. Foreign keys and triggers are not part of `LIKE` and are not
+/// copied. Runs in a transaction and returns the statements.
+pub async fn duplicate_table(
+    client: &Arc<Client>,
+    schema: &str,
+    table: &str,
+    options: &DuplicateTableOptions,
+) -> Result<Vec<String>, String> {
+    let source = qualified(schema, table)?;
+    let target_schema = options
+        .target_schema
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(schema);
+    let target = qualified(target_schema, &options.new_name)?;
+
+    let mut statements = vec![format!(
+        "CREATE TABLE {} (LIKE {} INCLUDING ALL{})",
+        target,
+        source,
+        if options.include_indexes { "" } else { " EXCLUDING INDEXES" }
+    )];
+
+    // Sequence settings for identity columns and for serial columns, i.e.
+    // ones whose default draws from a sequence they own
+    let rows = client
+        .query(
+            "SELECT a.attname::text, a.attidentity, s.seqincrement > 0,
+                    CASE WHEN a.attidentity = '' THEN
+                        format('AS %s INCREMENT BY %s MINVALUE %s MAXVALUE %s START WITH %s CACHE %s %s',
+                               s.seqtypid::regtype, s.seqincrement, s.seqmin, s.seqmax,
+                               s.seqstart, s.seqcache, CASE WHEN s.seqcycle THEN 'CYCLE' ELSE 'NO CYCLE' END)
+                    END
+             FROM pg_attribute a
+             LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+             LEFT JOIN pg_sequence s
+                ON s.seqrelid = pg_get_serial_sequence($1, a.attname)::regclass
+                AND (a.attidentity <> '' OR pg_get_expr(d.adbin, d.adrelid) LIKE 'nextval(%')
+             WHERE a.attrelid = $1::text::regclass
+                AND a.attnum > 0
+                AND NOT a.attisdropped
+                AND a.attgenerated = ''
+             ORDER BY a.attnum",
+            &[&source],
+        )
+        .await
+        .map_err(|e| format_db_error(&e))?;
+
+    // (column, ascending) of every column backed by a sequence of the copy
+    let mut sequence_columns: Vec<(String, bool)> = Vec::new();
+    for row in &rows {
+        let column: String = row.get(0);
+        let Some(ascending) = row.get::<_, Option<bool>>(2) else {
+            continue;
+        };
+        if let Some(sequence_options) = row.get::<_, Option<String>>(3) {
+            let sequence = unused_relation_name(
+                client,
+                target_schema,
+                &format!("{}_{}_seq", options.new_name, column),
+            )
+            .await?;
+            statements.push(format!(
+                "CREATE SEQUENCE {} {} OWNED BY {}.{}",
+                sequence,
+                sequence_options,
+                target,
+                quote_ident(&column)
+            ));
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT nextval({}::regclass)",
+                target,
+                quote_ident(&column),
+                quote_literal(&sequence)
+            ));
+        }
+        sequence_columns.push((column, ascending));
+    }
+
+    if options.include_data {
+        let columns = rows
+            .iter()
+            .map(|row| quote_ident(&row.get::<_, String>(0)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let identity_always = rows.iter().any(|row| row.get::<_, i8>(1) == b'a' as i8);
+        statements.push(format!(
+            "INSERT INTO {} ({}){} SELECT {} FROM {}",
+            target,
+            columns,
+            if identity_always { " OVERRIDING SYSTEM VALUE" } else { "" },
+            columns,
+            source
+        ));
+        // setval ignores the NULL of an empty table
+        for (column, ascending) in &sequence_columns {
+            statements.push(format!(
+                "SELECT setval(pg_get_serial_sequence({}, {}), {}({})) FROM {}",
+                quote_literal(&target),
+                quote_literal(column),
+                if *ascending { "max" } else { "min" },
+                quote_ident(column),
+                target
+            ));
+        }
+    }
+
+    client.execute("BEGIN", &[]).await.map_err(|e| format_db_error(&e))?;
+
+    for sql in &statements {
+        if let Err(e) = client.batch_execute(sql).await {
+            client.execute("ROLLBACK", &[]).await.ok();
+            return Err(format_db_error(&e));
+        }
+    }
+
+    if let Err(e) = client.execute("COMMIT", &[]).await {
+        client.execute("ROLLBACK", &[]).await.ok();
+        return Err(format_db_error(&e));
+    }
+
+    Ok(statements)
+}
+
+/// `name` in `schema`, with a numeric suffix if a relation already uses it.
+async fn unused_relation_name(client: &Arc<Client>, schema: &str, name: &str) -> Result<String, String> {
+    let mut candidate = name.to_string();
+    let mut suffix = 0;
+    loop {
+        let qualified = qualified(schema, &candidate)?;
+        let taken: bool = client
+            .query_one("SELECT to_regclass($1) IS NOT NULL", &[&qualified])
+            .await
+            .map_err(|e| format_db_error(&e))?
+            .get(0);
+        if !taken {
+            return Ok(qualified);
+        }
+        suffix += 1;
+        candidate = format!("{}{}", name, suffix);
+    }
+}
@@ -0,0 +1,775 @@
+use std::error::Error;
+use std::fmt::Write as _;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use tokio_postgres::types::{FromSql, Kind, Type};
+use tokio_postgres::Column;
+
+use super::queries::quote_ident;
+
+// ── Binary value decoding ───────────────────────────────────────────────
+//
+// Every value arrives in PostgreSQL's binary format. Known types are decoded
+// here into JSON; anything else reports `Unsupported` so the caller can
+// select that column through a `::text` cast instead.
+
+/// Largest integer a JavaScript number can hold exactly (2^53 - 1).
+const JS_MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+type DecodeResult = Result<serde_json::Value, Box<dyn Error + Sync + Send>>;
+
+#[derive(Debug)]
+struct Unsupported;
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "desteklenmeyen veri tipi")
+    }
+}
+
+impl Error for Unsupported {}
+
+#[derive(Debug)]
+struct Malformed(&'static str);
+
+impl std::fmt::Display for Malformed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "geçersiz {} verisi", self.0)
+    }
+}
+
+impl Error for Malformed {}
+
+/// A column value decoded into JSON.
+pub struct PgValue(pub serde_json::Value);
+
+impl<'a> FromSql<'a> for PgValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        decode(ty, raw).map(PgValue)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_supported(ty)
+    }
+}
+
+/// Whether `decode` understands the type; other columns need a text cast.
+pub fn is_supported(ty: &Type) -> bool {
+    match ty.kind() {
+        Kind::Enum(_) => true,
+        Kind::Array(elem) | Kind::Range(elem) | Kind::Domain(elem) => is_supported(elem),
+        Kind::Multirange(range) => is_supported(range),
+        Kind::Composite(fields) => fields.iter().all(|f| is_supported(f.type_())),
+        Kind::Pseudo => false,
+        _ => is_text_like(ty) || SIMPLE_TYPES.contains(ty),
+    }
+}
+
+const SIMPLE_TYPES: &[Type] = &[
+    Type::BOOL,
+    Type::INT2,
+    Type::INT4,
+    Type::INT8,
+    Type::OID,
+    Type::FLOAT4,
+    Type::FLOAT8,
+    Type::NUMERIC,
+    Type::MONEY,
+    Type::CHAR,
+    Type::JSON,
+    Type::JSONB,
+    Type::UUID,
+    Type::BYTEA,
+    Type::DATE,
+    Type::TIME,
+    Type::TIMETZ,
+    Type::TIMESTAMP,
+    Type::TIMESTAMPTZ,
+    Type::INTERVAL,
+    Type::INET,
+    Type::CIDR,
+    Type::MACADDR,
+    Type::MACADDR8,
+    Type::BIT,
+    Type::VARBIT,
+    Type::POINT,
+];
+
+/// Types whose binary representation is the UTF-8 text itself.
+fn is_text_like(ty: &Type) -> bool {
+    matches!(
+        *ty,
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN | Type::XML
+    ) || (ty.kind() == &Kind::Simple && ty.name() == "citext")
+}
+
+fn read_i16(raw: &[u8], at: usize) -> Result<i16, Malformed> {
+    raw.get(at..at + 2)
+        .map(|b| i16::from_be_bytes([b[0], b[1]]))
+        .ok_or(Malformed("int2"))
+}
+
+fn read_i32(raw: &[u8], at: usize) -> Result<i32, Malformed> {
+    raw.get(at..at + 4)
+        .map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Malformed("int4"))
+}
+
+fn read_i64(raw: &[u8], at: usize) -> Result<i64, Malformed> {
+    raw.get(at..at + 8)
+        .map(|b| i64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+        .ok_or(Malformed("int8"))
+}
+
+fn text(raw: &[u8]) -> DecodeResult {
+    Ok(serde_json::Value::String(std::str::from_utf8(raw)?.to_string()))
+}
+
+fn int8_json(v: i64) -> serde_json::Value {
+    if (-JS_MAX_SAFE_INTEGER..=JS_MAX_SAFE_INTEGER).contains(&v) {
+        serde_json::Value::Number(v.into())
+    } else {
+        serde_json::Value::String(v.to_string())
+    }
+}
+
+fn float_json(v: f64) -> serde_json::Value {
+    match serde_json::Number::from_f64(v) {
+        Some(n) => serde_json::Value::Number(n),
+        None if v.is_nan() => serde_json::Value::String("NaN".to_string()),
+        None if v > 0.0 => serde_json::Value::String("Infinity".to_string()),
+        None => serde_json::Value::String("-Infinity".to_string()),
+    }
+}
+
+pub fn decode(ty: &Type, raw: &[u8]) -> DecodeResult {
+    match ty.kind() {
+        Kind::Enum(_) => return text(raw),
+        Kind::Domain(base) => return decode(base, raw),
+        Kind::Array(elem) => return decode_array(elem, raw),
+        Kind::Range(elem) => return decode_range(elem, raw).map(serde_json::Value::String),
+        Kind::Multirange(range) => return decode_multirange(range, raw),
+        Kind::Composite(fields) => return decode_composite(fields, raw),
+        _ => {}
+    }
+
+    if is_text_like(ty) {
+        return text(raw);
+    }
+
+    match *ty {
+        Type::BOOL => Ok(serde_json::Value::Bool(bool::from_sql(ty, raw)?)),
+        Type::INT2 => Ok(i16::from_sql(ty, raw)?.into()),
+        Type::INT4 => Ok(i32::from_sql(ty, raw)?.into()),
+        Type::INT8 => Ok(int8_json(i64::from_sql(ty, raw)?)),
+        Type::OID => Ok(u32::from_sql(ty, raw)?.into()),
+        Type::FLOAT4 => Ok(float_json(f32::from_sql(ty, raw)? as f64)),
+        Type::FLOAT8 => Ok(float_json(f64::from_sql(ty, raw)?)),
+        Type::NUMERIC => Ok(serde_json::Value::String(decode_numeric(raw)?)),
+        Type::MONEY => Ok(serde_json::Value::String(format_money(read_i64(raw, 0)?))),
+        Type::CHAR => Ok(serde_json::Value::String(
+            raw.first().map(|b| (*b as char).to_string()).unwrap_or_default(),
+        )),
+        Type::JSON => Ok(serde_json::from_slice(raw)?),
+        Type::JSONB => match raw.split_first() {
+            Some((1, rest)) => Ok(serde_json::from_slice(rest)?),
+            _ => Err(Box::new(Malformed("jsonb"))),
+        },
+        Type::UUID => Ok(serde_json::Value::String(format_uuid(raw)?)),
+        Type::BYTEA => Ok(serde_json::Value::String(format_bytea(raw))),
+        Type::DATE => Ok(serde_json::Value::String(format_date(read_i32(raw, 0)?))),
+        Type::TIME => Ok(serde_json::Value::String(format_time(read_i64(raw, 0)?))),
+        Type::TIMETZ => Ok(serde_json::Value::String(format!(
+            "{}{}",
+            format_time(read_i64(raw, 0)?),
+            format_offset(-read_i32(raw, 8)?)
+        ))),
+        Type::TIMESTAMP => Ok(serde_json::Value::String(format_timestamp(read_i64(raw, 0)?))),
+        Type::TIMESTAMPTZ => {
+            let micros = read_i64(raw, 0)?;
+            let ts = format_timestamp(micros);
+            Ok(serde_json::Value::String(
+                if micros == i64::MAX || micros == i64::MIN { ts } else { format!("{}+00", ts) },
+            ))
+        }
+        Type::INTERVAL => Ok(serde_json::Value::String(format_interval(
+            read_i64(raw, 0)?,
+            read_i32(raw, 8)?,
+            read_i32(raw, 12)?,
+        ))),
+        Type::INET | Type::CIDR => Ok(serde_json::Value::String(format_inet(raw)?)),
+        Type::MACADDR | Type::MACADDR8 => Ok(serde_json::Value::String(
+            raw.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"),
+        )),
+        Type::BIT | Type::VARBIT => Ok(serde_json::Value::String(format_bits(raw)?)),
+        Type::POINT => {
+            let x = f64::from_be_bytes(raw.get(0..8).ok_or(Malformed("point"))?.try_into()?);
+            let y = f64::from_be_bytes(raw.get(8..16).ok_or(Malformed("point"))?.try_into()?);
+            Ok(serde_json::Value::String(format!("({},{})", x, y)))
+        }
+        _ => Err(Box::new(Unsupported)),
+    }
+}
+
+/// Numeric is base-10000 digits with a weight and display scale; the
+/// result is kept as a string so no precision is lost.
+fn decode_numeric(raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    let ndigits = read_i16(raw, 0)? as usize;
+    let weight = read_i16(raw, 2)? as i32;
+    let sign = read_i16(raw, 4)? as u16;
+    let dscale = read_i16(raw, 6)? as usize;
+
+    match sign {
+        0xC000 => return Ok("NaN".to_string()),
+        0xD000 => return Ok("Infinity".to_string()),
+        0xF000 => return Ok("-Infinity".to_string()),
+        _ => {}
+    }
+
+    let digits: Vec<i16> = (0..ndigits)
+        .map(|i| read_i16(raw, 8 + i * 2))
+        .collect::<Result<_, _>>()?;
+
+    let mut out = String::new();
+    if sign == 0x4000 {
+        out.push('-');
+    }
+
+    // Integer part: groups 0..=weight
+    if weight < 0 {
+        out.push('0');
+    } else {
+        for i in 0..=weight as usize {
+            let d = digits.get(i).copied().unwrap_or(0);
+            if i == 0 {
+                write!(out, "{}", d)?;
+            } else {
+                write!(out, "{:04}", d)?;
+            }
+        }
+    }
+
+    if dscale > 0 {
+        let mut frac = String::new();
+        let mut group = weight + 1;
+        while frac.len() < dscale {
+            let d = if group < 0 {
+                0
+            } else {
+                digits.get(group as usize).copied().unwrap_or(0)
+            };
+            write!(frac, "{:04}", d)?;
+            group += 1;
+        }
+        frac.truncate(dscale);
+        out.push('.');
+        out.push_str(&frac);
+    }
+
+    Ok(out)
+}
+
+fn format_money(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+}
+
+fn format_uuid(raw: &[u8]) -> Result<String, Malformed> {
+    if raw.len() != 16 {
+        return Err(Malformed("uuid"));
+    }
+    let hex: String = raw.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+fn format_bytea(raw: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + raw.len() * 2);
+    out.push_str("\\x");
+    for b in raw {
+        let _ = write!(out, "{:02x}", b);
+    }
+    out
+}
+
+fn pg_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+fn format_date(days: i32) -> String {
+    match days {
+        i32::MAX => "infinity".to_string(),
+        i32::MIN => "-infinity".to_string(),
+        _ => pg_epoch()
+            .date()
+            .checked_add_signed(Duration::days(days as i64))
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| days.to_string()),
+    }
+}
+
+fn format_time(micros: i64) -> String {
+    let secs = micros.div_euclid(1_000_000);
+    let frac = micros.rem_euclid(1_000_000);
+    // 24:00:00 is a valid time value but not a valid NaiveTime
+    if secs >= 86_400 {
+        return "24:00:00".to_string();
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt(secs as u32, (frac * 1000) as u32)
+        .map(|t| t.format("%H:%M:%S%.f").to_string())
+        .unwrap_or_else(|| micros.to_string())
+}
+
+fn format_offset(offset_secs: i32) -> String {
+    let sign = if offset_secs < 0 { '-' } else { '+' };
+    let abs = offset_secs.unsigned_abs();
+    let (h, m, s) = (abs / 3600, (abs % 3600) / 60, abs % 60);
+    if s != 0 {
+        format!("{}{:02}:{:02}:{:02}", sign, h, m, s)
+    } else if m != 0 {
+        format!("{}{:02}:{:02}", sign, h, m)
+    } else {
+        format!("{}{:02}", sign, h)
+    }
+}
+
+fn format_timestamp(micros: i64) -> String {
+    match micros {
+        i64::MAX => "infinity".to_string(),
+        i64::MIN => "-infinity".to_string(),
+        _ => pg_epoch()
+            .checked_add_signed(Duration::microseconds(micros))
+            .map(|ts| ts.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            .unwrap_or_else(|| micros.to_string()),
+    }
+}
+
+/// Formats like PostgreSQL's default `postgres` interval style,
+/// e.g. `1 year 2 mons 3 days 04:05:06.5`.
+fn format_interval(micros: i64, days: i32, months: i32) -> String {
+    fn unit(n: i64, singular: &str, plural: &str) -> String {
+        format!("{} {}", n, if n == 1 || n == -1 { singular } else { plural })
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let (years, mons) = (months / 12, months % 12);
+    if years != 0 {
+        parts.push(unit(years as i64, "year", "years"));
+    }
+    if mons != 0 {
+        parts.push(unit(mons as i64, "mon", "mons"));
+    }
+    if days != 0 {
+        parts.push(unit(days as i64, "day", "days"));
+    }
+
+    if micros != 0 || parts.is_empty() {
+        let sign = if micros < 0 { "-" } else { "" };
+        let abs = micros.unsigned_abs();
+        let secs = abs / 1_000_000;
+        let frac = abs % 1_000_000;
+        let mut time = format!(
+            "{}{:02}:{:02}:{:02}",
+            sign,
+            secs / 3600,
+            (secs % 3600) / 60,
+            secs % 60
+        );
+        if frac != 0 {
+            let digits = format!("{:06}", frac);
+            time.push('.');
+            time.push_str(digits.trim_end_matches('0'));
+        }
+        parts.push(time);
+    }
+
+    parts.join(" ")
+}
+
+fn format_inet(raw: &[u8]) -> Result<String, Malformed> {
+    if raw.len() < 4 {
+        return Err(Malformed("inet"));
+    }
+    let (family, bits, is_cidr, len) = (raw[0], raw[1], raw[2] != 0, raw[3] as usize);
+    let addr = raw.get(4..4 + len).ok_or(Malformed("inet"))?;
+
+    let (ip, max_bits) = match (family, addr.len()) {
+        (2, 4) => (std::net::Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]).to_string(), 32),
+        (3, 16) => {
+            let octets: [u8; 16] = addr.try_into().map_err(|_| Malformed("inet"))?;
+            (std::net::Ipv6Addr::from(octets).to_string(), 128)
+        }
+        _ => return Err(Malformed("inet")),
+    };
+
+    Ok(if is_cidr || bits != max_bits {
+        format!("{}/{}", ip, bits)
+    } else {
+        ip
+    })
+}
+
+fn format_bits(raw: &[u8]) -> Result<String, Malformed> {
+    let len = read_i32(raw, 0)? as usize;
+    let data = raw.get(4..).ok_or(Malformed("bit"))?;
+    Ok((0..len)
+        .map(|i| {
+            let byte = data.get(i / 8).copied().unwrap_or(0);
+            if byte & (0x80 >> (i % 8)) != 0 { '1' } else { '0' }
+        })
+        .collect())
+}
+
+/// Reads a length-prefixed element; `None` for SQL NULL.
+fn read_element(raw: &[u8], at: &mut usize) -> Result<Option<std::ops::Range<usize>>, Malformed> {
+    let len = read_i32(raw, *at)?;
+    *at += 4;
+    if len < 0 {
+        return Ok(None);
+    }
+    let range = *at..*at + len as usize;
+    if range.end > raw.len() {
+        return Err(Malformed("eleman"));
+    }
+    *at = range.end;
+    Ok(Some(range))
+}
+
+fn decode_array(elem: &Type, raw: &[u8]) -> DecodeResult {
+    let ndim = read_i32(raw, 0)? as usize;
+    if ndim == 0 {
+        return Ok(serde_json::Value::Array(Vec::new()));
+    }
+
+    let dims: Vec<usize> = (0..ndim)
+        .map(|d| read_i32(raw, 12 + d * 8).map(|n| n.max(0) as usize))
+        .collect::<Result<_, _>>()?;
+
+    let mut at = 12 + ndim * 8;
+    let total: usize = dims.iter().product();
+    let mut flat = Vec::with_capacity(total);
+    for _ in 0..total {
+        flat.push(match read_element(raw, &mut at)? {
+            Some(range) => decode(elem, &raw[range])?,
+            None => serde_json::Value::Null,
+        });
+    }
+
+    // Fold the flat element list into nested arrays, innermost dimension first
+    for &size in dims.iter().skip(1).rev() {
+        let mut chunked = Vec::with_capacity(flat.len() / size.max(1));
+        let mut iter = flat.into_iter();
+        loop {
+            let chunk: Vec<serde_json::Value> = iter.by_ref().take(size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            chunked.push(serde_json::Value::Array(chunk));
+        }
+        flat = chunked;
+    }
+
+    Ok(serde_json::Value::Array(flat))
+}
+
+/// Renders a range bound the way PostgreSQL's range_out does: plain
+/// values as-is, anything with spaces or delimiters double-quoted.
+fn range_bound_text(value: &serde_json::Value) -> String {
+    let s = match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if s.chars().any(|c| c.is_whitespace() || "\"\\,()[]".contains(c)) {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s
+    }
+}
+
+fn decode_range(elem: &Type, raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    const EMPTY: u8 = 0x01;
+    const LB_INC: u8 = 0x02;
+    const UB_INC: u8 = 0x04;
+    const LB_INF: u8 = 0x08;
+    const UB_INF: u8 = 0x10;
+
+    let flags = *raw.first().ok_or(Malformed("range"))?;
+    if flags & EMPTY != 0 {
+        return Ok("empty".to_string());
+    }
+
+    let mut at = 1;
+    let mut bound = |infinite: bool| -> Result<String, Box<dyn Error + Sync + Send>> {
+        if infinite {
+            return Ok(String::new());
+        }
+        match read_element(raw, &mut at)? {
+            Some(range) => Ok(range_bound_text(&decode(elem, &raw[range])?)),
+            None => Ok(String::new()),
+        }
+    };
+
+    let lower = bound(flags & LB_INF != 0)?;
+    let upper = bound(flags & UB_INF != 0)?;
+
+    Ok(format!(
+        "{}{},{}{}",
+        if flags & LB_INC != 0 { '[' } else { '(' },
+        lower,
+        upper,
+        if flags & UB_INC != 0 { ']' } else { ')' }
+    ))
+}
+
+fn decode_multirange(range_type: &Type, raw: &[u8]) -> DecodeResult {
+    let elem = match range_type.kind() {
+        Kind::Range(elem) => elem,
+        _ => return Err(Box::new(Unsupported)),
+    };
+    let count = read_i32(raw, 0)?.max(0) as usize;
+    let mut at = 4;
+    let mut ranges = Vec::with_capacity(count);
+    for _ in 0..count {
+        if let Some(range) = read_element(raw, &mut at)? {
+            ranges.push(decode_range(elem, &raw[range])?);
+        }
+    }
+    Ok(serde_json::Value::String(format!("{{{}}}", ranges.join(","))))
+}
+
+fn decode_composite(fields: &[tokio_postgres::types::Field], raw: &[u8]) -> DecodeResult {
+    let count = read_i32(raw, 0)?.max(0) as usize;
+    let mut at = 4;
+    let mut obj = serde_json::Map::new();
+    for i in 0..count {
+        // Each field carries its own type oid before the value
+        at += 4;
+        let value = match read_element(raw, &mut at)? {
+            Some(range) => match fields.get(i) {
+                Some(field) => decode(field.type_(), &raw[range])?,
+                None => serde_json::Value::Null,
+            },
+            None => serde_json::Value::Null,
+        };
+        let name = fields
+            .get(i)
+            .map(|f| f.name().to_string())
+            .unwrap_or_else(|| format!("f{}", i + 1));
+        obj.insert(name, value);
+    }
+    Ok(serde_json::Value::Object(obj))
+}
+
+/// Wraps a row-returning query so that columns `decode` can't handle come
+/// back as text. Returns `Ok(None)` when nothing needs casting, and an
+/// error when there are such columns but the statement can't be wrapped.
+pub fn text_cast_query(sql: &str, columns: &[Column]) -> Result<Option<String>, String> {
+    let columns: Vec<(&str, &Type)> = columns.iter().map(|c| (c.name(), c.type_())).collect();
+    text_cast_sql(sql, &columns)
+}
+
+/// Error for columns that can't be decoded, e.g. when the wrapping query
+/// from `text_cast_query` fails to prepare.
+pub fn undecodable_error(columns: &[Column]) -> String {
+    let columns: Vec<(&str, &Type)> = columns.iter().map(|c| (c.name(), c.type_())).collect();
+    undecodable_columns_error(&columns)
+}
+
+fn undecodable_columns_error(columns: &[(&str, &Type)]) -> String {
+    let names: Vec<String> = columns
+        .iter()
+        .filter(|(_, ty)| !is_supported(ty))
+        .map(|(name, ty)| format!("{} ({})", name, ty.name()))
+        .collect();
+    format!(
+        "Şu kolonlar okunamıyor: {}. Sorguda bu kolonları ::text ile dönüştürün",
+        names.join(", ")
+    )
+}
+
+/// First keyword of a statement, after leading comments and parentheses.
+fn leading_keyword(sql: &str) -> String {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            break;
+        }
+    }
+    rest.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// The subquery's columns are renamed by position (`c1, c2, …`) so that
+/// repeated names, as in `SELECT * FROM a JOIN b`, can still be addressed;
+/// the outer select restores the original names. Only queries can be a
+/// subquery; a `WITH` holding data-modifying statements fails to prepare.
+fn text_cast_sql(sql: &str, columns: &[(&str, &Type)]) -> Result<Option<String>, String> {
+    if columns.iter().all(|(_, ty)| is_supported(ty)) {
+        return Ok(None);
+    }
+    if !matches!(leading_keyword(sql).as_str(), "SELECT" | "VALUES" | "WITH" | "TABLE") {
+        return Err(undecodable_columns_error(columns));
+    }
+
+    let aliases: Vec<String> = (1..=columns.len()).map(|i| format!("c{}", i)).collect();
+    let select: Vec<String> = columns
+        .iter()
+        .zip(aliases.iter())
+        .map(|((name, ty), alias)| {
+            let cast = if is_supported(ty) { "" } else { "::text" };
+            format!("dbleaf_q.{}{} AS {}", alias, cast, quote_ident(name))
+        })
+        .collect();
+
+    // The newline ends a trailing `--` comment before the closing paren
+    Ok(Some(format!(
+        "SELECT {} FROM ({}\n) AS dbleaf_q({})",
+        select.join(", "),
+        sql.trim().trim_end_matches(';'),
+        aliases.join(", ")
+    )))
+}
+
+/// Builds a select list for the given columns, casting unsupported types to text.
+pub fn select_list(columns: &[Column], qualifier: Option<&str>) -> String {
+    columns
+        .iter()
+        .map(|c| {
+            let name = quote_ident(c.name());
+            let expr = match qualifier {
+                Some(q) => format!("{}.{}", q, name),
+                None => name.clone(),
+            };
+            if is_supported(c.type_()) {
+                expr
+            } else {
+                format!("{}::text AS {}", expr, name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn decoded(ty: &Type, raw_hex: &str) -> serde_json::Value {
+        decode(ty, &hex(raw_hex)).unwrap()
+    }
+
+    #[test]
+    fn int8_beyond_safe_range_is_a_string() {
+        assert_eq!(decode(&Type::INT8, &42i64.to_be_bytes()).unwrap(), json!(42));
+        assert_eq!(
+            decode(&Type::INT8, &i64::MAX.to_be_bytes()).unwrap(),
+            json!("9223372036854775807")
+        );
+    }
+
+    #[test]
+    fn numeric_keeps_every_digit() {
+        assert_eq!(decoded(&Type::NUMERIC, "0003000100000003000109291a7c"), json!("12345.678"));
+        assert_eq!(decoded(&Type::NUMERIC, "0001ffff4000000201f4"), json!("-0.05"));
+    }
+
+    #[test]
+    fn multidimensional_arrays_nest_with_nulls() {
+        let raw = "00000002000000010000001700000002000000010000000200000001\
+                   000000040000000100000004000000020000000400000003ffffffff";
+        assert_eq!(decoded(&Type::INT4_ARRAY, raw), json!([[1, 2], [3, null]]));
+    }
+
+    #[test]
+    fn temporal_values_are_formatted() {
+        assert_eq!(decoded(&Type::DATE, "00002279"), json!("2024-02-29"));
+        assert_eq!(decoded(&Type::TIMESTAMP, "0002b5843c5d9da0"), json!("2024-02-29 13:45:06.500"));
+        assert_eq!(
+            decoded(&Type::INTERVAL, "000000036c8bc080000000030000000e"),
+            json!("1 year 2 mons 3 days 04:05:06")
+        );
+    }
+
+    #[test]
+    fn ranges_and_network_types() {
+        assert_eq!(decoded(&Type::INT4_RANGE, "020000000400000001000000040000000a"), json!("[1,10)"));
+        assert_eq!(decoded(&Type::INET, "02180004c0a80001"), json!("192.168.0.1/24"));
+    }
+
+    #[test]
+    fn unsupported_types_report_an_error() {
+        assert!(!is_supported(&Type::TS_VECTOR));
+        assert!(decode(&Type::TS_VECTOR, &[]).is_err());
+    }
+
+    #[test]
+    fn text_cast_is_skipped_when_everything_decodes() {
+        assert_eq!(text_cast_sql("SELECT 1", &[("a", &Type::INT4)]), Ok(None));
+    }
+
+    #[test]
+    fn text_cast_aliases_columns_by_position() {
+        let sql = text_cast_sql(
+            "SELECT * FROM a JOIN b USING (k);",
+            &[("id", &Type::INT4), ("id", &Type::TS_VECTOR), ("Doc", &Type::TS_VECTOR)],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT dbleaf_q.c1 AS \"id\", dbleaf_q.c2::text AS \"id\", dbleaf_q.c3::text AS \"Doc\" \
+             FROM (SELECT * FROM a JOIN b USING (k)\n) AS dbleaf_q(c1, c2, c3)"
+        );
+    }
+
+    #[test]
+    fn text_cast_ends_trailing_comments() {
+        let sql = text_cast_sql("SELECT v FROM t -- vectors", &[("v", &Type::TS_VECTOR)])
+            .unwrap()
+            .unwrap();
+        assert!(sql.contains("FROM (SELECT v FROM t -- vectors\n) AS dbleaf_q(c1)"));
+    }
+
+    #[test]
+    fn text_cast_wraps_queries_after_comments() {
+        let columns = [("v", &Type::TS_VECTOR)];
+        assert!(text_cast_sql("/* report */ (VALUES (1))", &columns).is_ok());
+        assert!(text_cast_sql("-- report\nWITH x AS (SELECT 1) TABLE x", &columns).is_ok());
+    }
+
+    #[test]
+    fn text_cast_rejects_statements_that_cant_be_wrapped() {
+        let err = text_cast_sql(
+            "INSERT INTO t (v) VALUES ('a') RETURNING id, v",
+            &[("id", &Type::INT4), ("v", &Type::TS_VECTOR)],
+        )
+        .unwrap_err();
+        assert!(err.contains("v (tsvector)"));
+        assert!(!err.contains("id"));
+    }
+}
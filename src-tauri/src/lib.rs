@@ -5,9 +5,16 @@ mod query_history;
 mod ssh_tunnel;
 mod storage;
 
+use db::activity::{create_monitor_manager, AppMonitorManager};
 use db::connection::{create_connection_manager, AppConnectionManager};
+use db::cursor::{create_cursor_manager, AppCursorManager};
+use db::maintenance::{create_maintenance_manager, AppMaintenanceManager};
 use db::models::ConnectionConfig;
-use db::queries;
+use db::{
+    activity, alter, changes, cursor, data_diff, databases, ddl, filter, functions, indexes,
+    maintenance, params, queries, references, roles, schema_diff, settings, stat_statements,
+    table_ops,
+};
 
 #[tauri::command]
 async fn test_connection(config: ConnectionConfig) -> Result<String, String> {
@@ -27,7 +34,11 @@ async fn connect_db(
 async fn disconnect_db(
     connection_id: String,
     state: tauri::State<'_, AppConnectionManager>,
+    cursors: tauri::State<'_, AppCursorManager>,
+    monitors: tauri::State<'_, AppMonitorManager>,
 ) -> Result<(), String> {
+    cursor::close_connection_cursors(&cursors, &connection_id).await;
+    activity::stop_connection_monitors(&monitors, &connection_id).await;
     let mut manager = state.lock().await;
     manager.disconnect(&connection_id)
 }
@@ -142,6 +153,725 @@ async fn list_sequences(
     queries::list_sequences(&client, &schema).await
 }
 
+#[tauri::command]
+async fn get_function_source(
+    connection_id: String,
+    schema: String,
+    name: String,
+    argument_types: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<functions::FunctionSource, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    functions::get_function_source(&client, &schema, &name, &argument_types).await
+}
+
+#[tauri::command]
+async fn deploy_function(
+    connection_id: String,
+    sql: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<functions::FunctionDeployResult, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    functions::deploy_function(&client, &sql).await
+}
+
+#[tauri::command]
+async fn list_materialized_views(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::MaterializedViewInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_materialized_views(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_foreign_tables(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::ForeignTableInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_foreign_tables(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_partitioned_tables(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::PartitionedTableInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_partitioned_tables(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_types(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::TypeInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_types(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_triggers(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::TriggerInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_triggers(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_policies(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::PolicyInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_policies(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_rules(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::RuleInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_rules(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_event_triggers(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::EventTriggerInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_event_triggers(&client).await
+}
+
+#[tauri::command]
+async fn list_extensions(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::ExtensionInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_extensions(&client).await
+}
+
+#[tauri::command]
+async fn list_roles(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::RoleInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_roles(&client).await
+}
+
+#[tauri::command]
+async fn list_object_privileges(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::ObjectPrivileges>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_object_privileges(&client, &schema).await
+}
+
+#[tauri::command]
+async fn list_default_privileges(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<queries::DefaultPrivilegeInfo>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::list_default_privileges(&client).await
+}
+
+#[tauri::command]
+async fn create_role(
+    connection_id: String,
+    options: roles::CreateRoleOptions,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    roles::create_role(&client, &options).await
+}
+
+#[tauri::command]
+async fn alter_role(
+    connection_id: String,
+    name: String,
+    options: roles::AlterRoleOptions,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    roles::alter_role(&client, &name, &options).await
+}
+
+#[tauri::command]
+async fn drop_role(
+    connection_id: String,
+    name: String,
+    reassign_to: Option<String>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    roles::drop_role(&client, &name, reassign_to.as_deref()).await
+}
+
+#[tauri::command]
+async fn grant_privileges(
+    connection_id: String,
+    change: roles::PrivilegeChange,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    roles::grant_privileges(&client, &change).await
+}
+
+#[tauri::command]
+async fn revoke_privileges(
+    connection_id: String,
+    change: roles::PrivilegeChange,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    roles::revoke_privileges(&client, &change).await
+}
+
+#[tauri::command]
+async fn get_server_activity(
+    connection_id: String,
+    options: Option<activity::ActivityOptions>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<activity::ActivitySnapshot, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    activity::get_server_activity(&client, &options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn start_activity_monitor(
+    connection_id: String,
+    monitor_id: String,
+    options: Option<activity::ActivityOptions>,
+    interval_ms: Option<u64>,
+    channel: tauri::ipc::Channel<activity::ActivitySnapshot>,
+    state: tauri::State<'_, AppConnectionManager>,
+    monitors: tauri::State<'_, AppMonitorManager>,
+) -> Result<(), String> {
+    let (client, session) = {
+        let manager = state.lock().await;
+        (
+            manager.get_client(&connection_id)?,
+            manager.open_session(&connection_id).await?,
+        )
+    };
+    activity::run_activity_monitor(
+        &client,
+        session,
+        &monitors,
+        &connection_id,
+        &monitor_id,
+        &options.unwrap_or_default(),
+        interval_ms,
+        channel,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn stop_activity_monitor(
+    monitor_id: String,
+    monitors: tauri::State<'_, AppMonitorManager>,
+) -> Result<(), String> {
+    activity::stop_activity_monitor(&monitors, &monitor_id).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn cancel_backend(
+    connection_id: String,
+    pid: i32,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<bool, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    activity::cancel_backend(&client, pid).await
+}
+
+#[tauri::command]
+async fn terminate_backend(
+    connection_id: String,
+    pid: i32,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<bool, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    activity::terminate_backend(&client, pid).await
+}
+
+#[tauri::command]
+async fn get_lock_tree(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<activity::LockNode>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    activity::get_lock_tree(&client).await
+}
+
+#[tauri::command]
+async fn terminate_root_blocker(
+    connection_id: String,
+    pid: i32,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<bool, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    activity::terminate_root_blocker(&client, pid).await
+}
+
+#[tauri::command]
+async fn run_maintenance(
+    connection_id: String,
+    operation_id: String,
+    request: maintenance::MaintenanceRequest,
+    channel: tauri::ipc::Channel<maintenance::MaintenanceProgress>,
+    state: tauri::State<'_, AppConnectionManager>,
+    operations: tauri::State<'_, AppMaintenanceManager>,
+) -> Result<maintenance::MaintenanceResult, String> {
+    let (client, session) = {
+        let manager = state.lock().await;
+        (
+            manager.get_client(&connection_id)?,
+            manager.open_session(&connection_id).await?,
+        )
+    };
+    let operation = maintenance::CancellableOperation {
+        operations: &operations,
+        connection_id: &connection_id,
+        operation_id: &operation_id,
+    };
+    maintenance::run_maintenance(&client, session, operation, &request, channel).await
+}
+
+#[tauri::command]
+async fn cancel_maintenance(
+    operation_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+    operations: tauri::State<'_, AppMaintenanceManager>,
+) -> Result<bool, String> {
+    let Some((connection_id, pid)) = maintenance::mark_cancelled(&operations, &operation_id).await
+    else {
+        return Ok(false);
+    };
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    maintenance::cancel_maintenance(&client, pid).await
+}
+
+#[tauri::command]
+async fn get_maintenance_stats(
+    connection_id: String,
+    schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<maintenance::TableMaintenanceStats>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    maintenance::get_maintenance_stats(&client, &schema).await
+}
+
+#[tauri::command]
+async fn get_stat_statements_status(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<stat_statements::StatStatementsStatus, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    stat_statements::get_stat_statements_status(&client).await
+}
+
+#[tauri::command]
+async fn get_top_statements(
+    connection_id: String,
+    options: Option<stat_statements::StatStatementsOptions>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<stat_statements::StatementStats>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    stat_statements::get_top_statements(&client, &options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn reset_stat_statements(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<(), String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    stat_statements::reset_stat_statements(&client).await
+}
+
+#[tauri::command]
+async fn list_settings(
+    connection_id: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<settings::SettingCategory>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    settings::list_settings(&client).await
+}
+
+#[tauri::command]
+async fn apply_setting(
+    connection_id: String,
+    change: settings::SettingChange,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<settings::SettingChangeResult, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    settings::apply_setting(&client, &change).await
+}
+
+#[tauri::command]
+async fn create_database(
+    connection_id: String,
+    options: databases::CreateDatabaseOptions,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::create_database(&client, &options).await
+}
+
+#[tauri::command]
+async fn rename_database(
+    connection_id: String,
+    name: String,
+    new_name: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::rename_database(&client, &name, &new_name).await
+}
+
+#[tauri::command]
+async fn drop_database(
+    connection_id: String,
+    name: String,
+    force: Option<bool>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::drop_database(&client, &name, force.unwrap_or(false)).await
+}
+
+#[tauri::command]
+async fn create_schema(
+    connection_id: String,
+    name: String,
+    owner: Option<String>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::create_schema(&client, &name, owner.as_deref()).await
+}
+
+#[tauri::command]
+async fn rename_schema(
+    connection_id: String,
+    name: String,
+    new_name: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::rename_schema(&client, &name, &new_name).await
+}
+
+#[tauri::command]
+async fn preview_drop_schema(
+    connection_id: String,
+    name: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<databases::SchemaDropPreview, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::preview_drop_schema(&client, &name).await
+}
+
+#[tauri::command]
+async fn drop_schema(
+    connection_id: String,
+    name: String,
+    cascade: Option<bool>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    databases::drop_schema(&client, &name, cascade.unwrap_or(false)).await
+}
+
+#[tauri::command]
+async fn truncate_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    restart_identity: Option<bool>,
+    cascade: Option<bool>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::truncate_table(
+        &client,
+        &schema,
+        &table,
+        restart_identity.unwrap_or(false),
+        cascade.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn preview_drop_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<table_ops::TableDropPreview, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::preview_drop_table(&client, &schema, &table).await
+}
+
+#[tauri::command]
+async fn drop_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    cascade: Option<bool>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::drop_table(&client, &schema, &table, cascade.unwrap_or(false)).await
+}
+
+#[tauri::command]
+async fn rename_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    new_name: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::rename_table(&client, &schema, &table, &new_name).await
+}
+
+#[tauri::command]
+async fn set_table_schema(
+    connection_id: String,
+    schema: String,
+    table: String,
+    new_schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::set_table_schema(&client, &schema, &table, &new_schema).await
+}
+
+#[tauri::command]
+async fn duplicate_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    options: table_ops::DuplicateTableOptions,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<String>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    table_ops::duplicate_table(&client, &schema, &table, &options).await
+}
+
+#[tauri::command]
+async fn get_table_ddl(
+    connection_id: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    ddl::get_table_ddl(&client, &schema, &table).await
+}
+
+#[tauri::command]
+async fn preview_table_changes(
+    schema: String,
+    table: String,
+    changes: Vec<alter::TableChange>,
+) -> Result<Vec<String>, String> {
+    alter::build_alter_statements(&schema, &table, &changes)
+}
+
+#[tauri::command]
+async fn apply_table_changes(
+    connection_id: String,
+    schema: String,
+    table: String,
+    changes: Vec<alter::TableChange>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<String>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    alter::apply_table_changes(&client, &schema, &table, &changes).await
+}
+
+#[tauri::command]
+async fn create_index(
+    connection_id: String,
+    operation_id: String,
+    schema: String,
+    table: String,
+    options: indexes::CreateIndexOptions,
+    channel: tauri::ipc::Channel<indexes::IndexBuildProgress>,
+    state: tauri::State<'_, AppConnectionManager>,
+    operations: tauri::State<'_, AppMaintenanceManager>,
+) -> Result<indexes::CreateIndexResult, String> {
+    let (client, session) = {
+        let manager = state.lock().await;
+        (
+            manager.get_client(&connection_id)?,
+            manager.open_session(&connection_id).await?,
+        )
+    };
+    let operation = maintenance::CancellableOperation {
+        operations: &operations,
+        connection_id: &connection_id,
+        operation_id: &operation_id,
+    };
+    indexes::create_index(&client, session, operation, &schema, &table, &options, channel).await
+}
+
+#[tauri::command]
+async fn drop_index(
+    connection_id: String,
+    schema: String,
+    name: String,
+    concurrently: Option<bool>,
+    cascade: Option<bool>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<String, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    indexes::drop_index(
+        &client,
+        &schema,
+        &name,
+        concurrently.unwrap_or(false),
+        cascade.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn get_index_health(
+    connection_id: String,
+    schema: Option<String>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<indexes::IndexHealthIssue>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    indexes::get_index_health(&client, schema.as_deref()).await
+}
+
+#[tauri::command]
+async fn compare_schemas(
+    source_connection_id: String,
+    source_schema: String,
+    target_connection_id: String,
+    target_schema: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<schema_diff::SchemaDiffResult, String> {
+    let manager = state.lock().await;
+    let source = manager.get_client(&source_connection_id)?;
+    let target = manager.get_client(&target_connection_id)?;
+    schema_diff::compare_schemas(&source, &source_schema, &target, &target_schema).await
+}
+
+#[tauri::command]
+async fn compare_table_data(
+    source_connection_id: String,
+    source_schema: String,
+    source_table: String,
+    target_connection_id: String,
+    target_schema: String,
+    target_table: String,
+    options: data_diff::DataDiffOptions,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<data_diff::DataDiffResult, String> {
+    let (source, target) = {
+        let manager = state.lock().await;
+        (
+            manager.open_session(&source_connection_id).await?,
+            manager.open_session(&target_connection_id).await?,
+        )
+    };
+    data_diff::compare_table_data(
+        source,
+        &source_schema,
+        &source_table,
+        target,
+        &target_schema,
+        &target_table,
+        &options,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn get_row_identity(
+    connection_id: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<queries::RowIdentity, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    queries::get_row_identity(&client, &schema, &table).await
+}
+
 #[tauri::command]
 async fn query_table_data(
     connection_id: String,
@@ -151,22 +881,82 @@ async fn query_table_data(
     page_size: i64,
     sort_column: Option<String>,
     sort_direction: Option<String>,
+    sort: Option<Vec<filter::SortSpec>>,
+    filters: Option<filter::FilterGroup>,
+    count_mode: Option<queries::CountMode>,
+    count_cap: Option<i64>,
+    pagination: Option<queries::PaginationMode>,
+    after: Option<Vec<Option<String>>>,
+    with_row_ids: Option<bool>,
     state: tauri::State<'_, AppConnectionManager>,
 ) -> Result<queries::TableDataResult, String> {
     let manager = state.lock().await;
     let client = manager.get_client(&connection_id)?;
-    queries::query_table_data(&client, &schema, &table, page, page_size, sort_column, sort_direction).await
+    let options = queries::TableDataOptions {
+        page,
+        page_size,
+        sort_column,
+        sort_direction,
+        sort,
+        filters,
+        count_mode,
+        count_cap,
+        pagination,
+        after,
+        with_row_ids: with_row_ids.unwrap_or(false),
+    };
+    queries::query_table_data(&client, &schema, &table, options).await
 }
 
 #[tauri::command]
 async fn execute_query(
     connection_id: String,
     sql: String,
+    params: Option<Vec<params::QueryParam>>,
+    fetch_size: Option<usize>,
+    max_rows: Option<usize>,
     state: tauri::State<'_, AppConnectionManager>,
+    cursors: tauri::State<'_, AppCursorManager>,
 ) -> Result<queries::ExecuteQueryResult, String> {
+    let manager = state.lock().await;
+    cursor::execute_query(
+        &manager,
+        &cursors,
+        &connection_id,
+        &sql,
+        &params.unwrap_or_default(),
+        fetch_size,
+        max_rows,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn fetch_query_rows(
+    result_handle: String,
+    count: Option<usize>,
+    cursors: tauri::State<'_, AppCursorManager>,
+) -> Result<cursor::QueryRowsBatch, String> {
+    cursor::fetch_query_rows(&cursors, &result_handle, count).await
+}
+
+#[tauri::command]
+async fn close_query_result(
+    result_handle: String,
+    cursors: tauri::State<'_, AppCursorManager>,
+) -> Result<(), String> {
+    cursor::close_query_result(&cursors, &result_handle).await
+}
+
+#[tauri::command]
+async fn describe_query_params(
+    connection_id: String,
+    sql: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<params::QueryParamInfo>, String> {
     let manager = state.lock().await;
     let client = manager.get_client(&connection_id)?;
-    queries::execute_query(&client, &sql).await
+    params::describe_query_params(&client, &sql).await
 }
 
 #[tauri::command]
@@ -206,14 +996,16 @@ async fn update_row(
     update_columns: Vec<String>,
     update_values: Vec<Option<String>>,
     update_types: Vec<String>,
+    expected: Option<changes::RowExpectation>,
     state: tauri::State<'_, AppConnectionManager>,
-) -> Result<u64, String> {
+) -> Result<changes::UpdateRowResult, String> {
     let manager = state.lock().await;
     let client = manager.get_client(&connection_id)?;
     queries::update_row(
         &client, &schema, &table,
         pk_columns, pk_values, pk_types,
         update_columns, update_values, update_types,
+        expected,
     )
     .await
 }
@@ -227,7 +1019,7 @@ async fn insert_row(
     values: Vec<Option<String>>,
     column_types: Vec<String>,
     state: tauri::State<'_, AppConnectionManager>,
-) -> Result<u64, String> {
+) -> Result<changes::ReturnedRows, String> {
     let manager = state.lock().await;
     let client = manager.get_client(&connection_id)?;
     queries::insert_row(&client, &schema, &table, columns, values, column_types).await
@@ -248,6 +1040,60 @@ async fn delete_rows(
     queries::delete_rows(&client, &schema, &table, pk_columns, pk_types, pk_value_sets).await
 }
 
+#[tauri::command]
+async fn apply_changes(
+    connection_id: String,
+    schema: String,
+    table: String,
+    changes: Vec<changes::PendingChange>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<changes::ApplyChangesResult, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    changes::apply_changes(&client, &schema, &table, &changes).await
+}
+
+#[tauri::command]
+async fn list_table_references(
+    connection_id: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<references::TableReferences, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    references::list_table_references(&client, &schema, &table).await
+}
+
+#[tauri::command]
+async fn get_referenced_row(
+    connection_id: String,
+    schema: String,
+    table: String,
+    constraint_name: String,
+    values: Vec<Option<String>>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<references::ReferencedRow, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    references::get_referenced_row(&client, &schema, &table, &constraint_name, values).await
+}
+
+#[tauri::command]
+async fn list_referencing_rows(
+    connection_id: String,
+    schema: String,
+    table: String,
+    row: references::RowValues,
+    options: Option<references::ReferencingRowsOptions>,
+    state: tauri::State<'_, AppConnectionManager>,
+) -> Result<Vec<references::ReferencingGroup>, String> {
+    let manager = state.lock().await;
+    let client = manager.get_client(&connection_id)?;
+    references::list_referencing_rows(&client, &schema, &table, &row, &options.unwrap_or_default())
+        .await
+}
+
 #[tauri::command]
 async fn backup_database(
     config: ConnectionConfig,
@@ -312,11 +1158,12 @@ async fn clear_query_history() -> Result<(), String> {
 async fn explain_query(
     connection_id: String,
     sql: String,
+    params: Option<Vec<params::QueryParam>>,
     state: tauri::State<'_, AppConnectionManager>,
 ) -> Result<queries::ExplainResult, String> {
     let manager = state.lock().await;
     let client = manager.get_client(&connection_id)?;
-    queries::explain_query(&client, &sql).await
+    queries::explain_query(&client, &sql, &params.unwrap_or_default()).await
 }
 
 #[tauri::command]
@@ -365,6 +1212,9 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .manage(create_connection_manager())
+        .manage(create_cursor_manager())
+        .manage(create_monitor_manager())
+        .manage(create_maintenance_manager())
         .invoke_handler(tauri::generate_handler![
             test_connection,
             connect_db,
@@ -379,14 +1229,77 @@ pub fn run() {
             list_foreign_keys,
             list_functions,
             list_sequences,
+            get_function_source,
+            deploy_function,
+            list_materialized_views,
+            list_foreign_tables,
+            list_partitioned_tables,
+            list_types,
+            list_triggers,
+            list_policies,
+            list_rules,
+            list_event_triggers,
+            list_extensions,
+            list_roles,
+            list_object_privileges,
+            list_default_privileges,
+            create_role,
+            alter_role,
+            drop_role,
+            grant_privileges,
+            revoke_privileges,
+            get_server_activity,
+            start_activity_monitor,
+            stop_activity_monitor,
+            cancel_backend,
+            terminate_backend,
+            get_lock_tree,
+            terminate_root_blocker,
+            run_maintenance,
+            cancel_maintenance,
+            get_maintenance_stats,
+            get_stat_statements_status,
+            get_top_statements,
+            reset_stat_statements,
+            list_settings,
+            apply_setting,
+            create_database,
+            rename_database,
+            drop_database,
+            create_schema,
+            rename_schema,
+            preview_drop_schema,
+            drop_schema,
+            truncate_table,
+            preview_drop_table,
+            drop_table,
+            rename_table,
+            set_table_schema,
+            duplicate_table,
+            get_table_ddl,
+            preview_table_changes,
+            apply_table_changes,
+            create_index,
+            drop_index,
+            get_index_health,
+            compare_schemas,
+            compare_table_data,
+            get_row_identity,
             query_table_data,
             execute_query,
+            describe_query_params,
+            fetch_query_rows,
+            close_query_result,
             get_schema_completions,
             list_databases,
             export_table_data,
             update_row,
             insert_row,
             delete_rows,
+            apply_changes,
+            list_table_references,
+            get_referenced_row,
+            list_referencing_rows,
             backup_database,
             restore_database,
             add_query_history,
@@ -31,6 +31,7 @@ interface QuickQueryResult {
   execution_time_ms: number;
   is_select: boolean;
   affected_rows?: number;
+  result_handle: string | null;
 }
 
 interface PaletteItem {
@@ -123,6 +124,10 @@ export default function CommandPalette({ isOpen, onClose }: Props) {
         connectionId: activeConnectionId,
         sql: query.trim(),
       });
+      // Quick results are a preview; don't keep the cursor open
+      if (res.result_handle) {
+        invoke("close_query_result", { resultHandle: res.result_handle }).catch(() => {});
+      }
       setQuickResult(res);
     } catch (err: any) {
       setQuickError(typeof err === "string" ? err : err.message || String(err));
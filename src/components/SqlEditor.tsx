@@ -44,6 +44,16 @@ interface ExecuteQueryResult {
   row_count: number;
   execution_time_ms: number;
   is_select: boolean;
+  result_handle: string | null;
+  has_more: boolean;
+  truncated: boolean;
+}
+
+interface QueryRowsBatch {
+  rows: any[][];
+  row_count: number;
+  has_more: boolean;
+  truncated: boolean;
 }
 
 interface Props {
@@ -65,6 +75,18 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
   const [error, setError] = useState<string | null>(null);
   const [explainResult, setExplainResult] = useState<ExplainResult | null>(null);
   const [resultMode, setResultMode] = useState<"query" | "explain">("query");
+  const [isFetchingMore, setIsFetchingMore] = useState(false);
+  const resultHandleRef = useRef<string | null>(null);
+
+  // Release the server-side cursor of the previous result
+  const closeResultHandle = useCallback(() => {
+    const handle = resultHandleRef.current;
+    if (!handle) return;
+    resultHandleRef.current = null;
+    invoke("close_query_result", { resultHandle: handle }).catch(() => {});
+  }, []);
+
+  useEffect(() => closeResultHandle, [closeResultHandle]);
 
   // Build CodeMirror schema object from completions
   const cmSchema = useMemo(() => {
@@ -86,6 +108,7 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
     const sqlText = viewRef.current.state.doc.toString().trim();
     if (!sqlText) return;
 
+    closeResultHandle();
     setIsExecuting(true);
     setError(null);
     setResult(null);
@@ -99,6 +122,7 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
         connectionId: activeConnectionId,
         sql: sqlText,
       });
+      resultHandleRef.current = res.result_handle;
       setResult(res);
 
       // Record to query history
@@ -132,13 +156,43 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
     } finally {
       setIsExecuting(false);
     }
-  }, [activeConnectionId, connections]);
+  }, [activeConnectionId, connections, closeResultHandle]);
+
+  const fetchMoreRows = useCallback(async () => {
+    const handle = resultHandleRef.current;
+    if (!handle || isFetchingMore) return;
+
+    setIsFetchingMore(true);
+    try {
+      const batch = await invoke<QueryRowsBatch>("fetch_query_rows", {
+        resultHandle: handle,
+      });
+      if (!batch.has_more) resultHandleRef.current = null;
+      setResult((prev) =>
+        prev
+          ? {
+              ...prev,
+              rows: [...prev.rows, ...batch.rows],
+              row_count: batch.row_count,
+              has_more: batch.has_more,
+              truncated: batch.truncated,
+            }
+          : prev
+      );
+    } catch (err: any) {
+      resultHandleRef.current = null;
+      setError(err?.toString() || t("sql.executeError"));
+    } finally {
+      setIsFetchingMore(false);
+    }
+  }, [isFetchingMore]);
 
   const explainQuery = useCallback(async () => {
     if (!activeConnectionId || !viewRef.current) return;
     const sqlText = viewRef.current.state.doc.toString().trim();
     if (!sqlText) return;
 
+    closeResultHandle();
     setIsExecuting(true);
     setError(null);
     setResult(null);
@@ -184,7 +238,7 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
     } finally {
       setIsExecuting(false);
     }
-  }, [activeConnectionId, connections]);
+  }, [activeConnectionId, connections, closeResultHandle]);
 
   // Keep refs up to date so the keymap always calls the latest functions
   executeRef.current = executeQuery;
@@ -467,6 +521,19 @@ export default function SqlEditor({ initialSql = "", tabId: _tabId }: Props) {
                 <Clock className="h-3 w-3" />
                 <span>{result.execution_time_ms} ms</span>
               </div>
+              {result.truncated && (
+                <span className="text-warning">{t("sql.rowLimitReached")}</span>
+              )}
+              {result.has_more && (
+                <button
+                  onClick={fetchMoreRows}
+                  disabled={isFetchingMore}
+                  className="ml-auto flex items-center gap-1 rounded px-2 py-0.5 text-accent hover:bg-bg-hover disabled:opacity-50 transition-colors"
+                >
+                  {isFetchingMore && <Loader2 className="h-3 w-3 animate-spin" />}
+                  {t("sql.loadMore")}
+                </button>
+              )}
             </div>
           </>
         )}
@@ -27,6 +27,13 @@ export interface IndexInfo {
   is_primary: boolean;
   columns: string;
   index_type: string;
+  include_columns: string[];
+  predicate: string | null;
+  definition: string;
+  is_valid: boolean;
+  size_bytes: number;
+  size: string;
+  scans: number;
 }
 
 export interface ForeignKeyInfo {
@@ -42,11 +49,22 @@ export interface FunctionInfo {
   schema: string;
   return_type: string;
   argument_types: string;
+  kind: "function" | "procedure" | "aggregate" | "window";
 }
 
 export interface SequenceInfo {
   name: string;
   schema: string;
+  data_type: string;
+  start_value: string;
+  min_value: string;
+  max_value: string;
+  increment_by: string;
+  cycle: boolean;
+  cache_size: string;
+  last_value: string | null;
+  owner: string;
+  owned_by: string | null;
 }
 
 export interface CompletionTable {